@@ -105,7 +105,7 @@ mod tests {
             rng: FullScale::RNG8G,
         };
 
-        mag.change_settings(set).unwrap();
+        mag.change_settings_verified(set).unwrap();
 
         assert_eq!(mag.settings().unwrap(), set);
     }
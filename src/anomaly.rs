@@ -0,0 +1,139 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Error, Measurement, QMC8553L};
+
+/// Detects deviations in field strength from a captured baseline.
+///
+/// This is useful for metal detection or door-open sensors, where the presence of a magnet or
+/// ferrous object changes the magnitude of the field seen by the sensor.
+/// Only integer arithmetic is used.
+///
+/// ```no_run
+/// # fn main() {
+/// # let mock_i2c = embedded_hal_mock::i2c::Mock::new(&[]);
+/// use qmc5883l::{AnomalyDetector, QMC8553L, settings::Settings};
+/// let mut mag = QMC8553L::new(mock_i2c, Settings::default()).unwrap();
+///
+/// let mut detector = AnomalyDetector::new(500);
+/// detector.calibrate_baseline(&mut mag).unwrap();
+/// if detector.check(mag.read_all().unwrap()) {
+///     println!("Something magnetic is nearby!");
+/// }
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct AnomalyDetector {
+    baseline: Measurement,
+    baseline_magnitude: u32,
+    threshold: u32,
+}
+
+impl AnomalyDetector {
+    /// Create a detector which triggers when the field magnitude differs from the baseline by
+    /// more than `threshold` counts.
+    ///
+    /// The baseline starts at zero: see [`Self::calibrate_baseline`] and [`Self::set_baseline`].
+    #[must_use]
+    pub fn new(threshold: u16) -> Self {
+        Self {
+            threshold: threshold.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Capture the current reading from the device as the baseline.
+    pub fn calibrate_baseline<I: I2c>(
+        &mut self,
+        mag: &mut QMC8553L<I>,
+    ) -> Result<(), Error<I::Error>> {
+        self.set_baseline(mag.read_all()?);
+        Ok(())
+    }
+
+    /// Set the baseline explicitly.
+    pub fn set_baseline(&mut self, baseline: Measurement) {
+        self.baseline = baseline;
+        self.baseline_magnitude = isqrt(baseline.magnitude_squared());
+    }
+
+    /// Get the current baseline.
+    #[must_use]
+    pub fn baseline(&self) -> Measurement {
+        self.baseline
+    }
+
+    /// Set the threshold, in counts.
+    pub fn set_threshold(&mut self, threshold: u16) {
+        self.threshold = threshold.into();
+    }
+
+    /// Check whether the magnitude of `m` deviates from the baseline by more than the threshold.
+    #[must_use]
+    pub fn check(&self, m: Measurement) -> bool {
+        // Compare squared magnitudes to avoid taking a square root of every reading
+        let mag_sq = u64::from(m.magnitude_squared());
+        let upper = u64::from(self.baseline_magnitude + self.threshold).pow(2);
+        if mag_sq > upper {
+            return true;
+        }
+        match self.baseline_magnitude.checked_sub(self.threshold) {
+            Some(lower) => mag_sq < u64::from(lower).pow(2),
+            None => false,
+        }
+    }
+}
+
+/// Integer square root, rounding down.
+fn isqrt(val: u32) -> u32 {
+    if val < 2 {
+        return val;
+    }
+    // Newton's method, starting from an overestimate.
+    // Work in u64 so the sum can't overflow.
+    let val = u64::from(val);
+    let mut x = val;
+    let mut y = (x + 1) >> 1;
+    while y < x {
+        x = y;
+        y = (x + val / x) >> 1;
+    }
+    // The root of a u32 always fits in a u32
+    #[allow(clippy::cast_possible_truncation)]
+    let root = x as u32;
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_sqrt() {
+        for val in [0, 1, 2, 3, 4, 15, 16, 17, 1_000_000, u32::MAX] {
+            let root = u64::from(isqrt(val));
+            assert!(root * root <= u64::from(val));
+            assert!((root + 1) * (root + 1) > u64::from(val));
+        }
+    }
+
+    #[test]
+    fn detects_deviation() {
+        let mut detector = AnomalyDetector::new(100);
+        detector.set_baseline(Measurement::from([300, 400, 0]));
+
+        assert!(!detector.check(Measurement::from([0, 0, 500])));
+        assert!(!detector.check(Measurement::from([0, 0, 599])));
+        assert!(!detector.check(Measurement::from([0, 0, 401])));
+        assert!(detector.check(Measurement::from([0, 0, 601])));
+        assert!(detector.check(Measurement::from([0, 0, 399])));
+    }
+
+    #[test]
+    fn threshold_larger_than_baseline() {
+        let mut detector = AnomalyDetector::new(1000);
+        detector.set_baseline(Measurement::from([0, 0, 500]));
+
+        assert!(!detector.check(Measurement::default()));
+        assert!(detector.check(Measurement::from([0, 0, 1501])));
+    }
+}
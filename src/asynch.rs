@@ -0,0 +1,206 @@
+use embedded_hal::digital::InputPin;
+use embedded_hal::i2c::I2c;
+use embedded_hal_async::delay::DelayUs;
+use embedded_hal_async::digital::Wait;
+
+use crate::registers::{Registers, Status};
+use crate::{Error, Measurement, ReadyStrategy, QMC8553L};
+
+impl<I: I2c> QMC8553L<I> {
+    /// Wait until new data is ready, yielding to the executor while waiting.
+    ///
+    /// The [`ReadyStrategy`] set with [`Self::set_ready_strategy`] is followed:
+    /// - With [`ReadyStrategy::StatusBit`], this is the async counterpart to the polling in
+    ///   [`Self::read_all_when_ready`]: `delay` is awaited between polls of the status register
+    ///   (eg. an `embassy_time::Delay`), at the same interval.
+    ///   The status reads themselves are still blocking bus transactions.
+    /// - With [`ReadyStrategy::FixedRate`], no bus access is made: `delay` is awaited for one
+    ///   sample period at the configured [`crate::settings::OutputDataRate`], so the
+    ///   strategy's clock isn't needed.
+    ///
+    /// Where the DRDY pin is wired up, prefer [`Self::on_data_ready_async`], which awaits the
+    /// pin and needs no polling at all.
+    pub async fn wait_ready<D: DelayUs>(&mut self, delay: &mut D) -> Result<(), Error<I::Error>> {
+        if let ReadyStrategy::FixedRate { .. } = self.ready_strategy {
+            delay.delay_us(self.sample_period_us()).await;
+            return Ok(());
+        }
+        let interval = self.poll_interval_us();
+        while !self
+            .get_status()
+            .map_err(Error::I2c)?
+            .contains(Status::DRDY)
+        {
+            delay.delay_us(interval).await;
+        }
+        Ok(())
+    }
+
+    /// Repeatedly wait for a rising edge on the interrupt pin, read the data and pass it to `f`.
+    ///
+    /// This is the async counterpart to [`Self::on_data_ready`]: rather than polling the pin,
+    /// the edge is awaited, so the executor can sleep until the device signals new data.
+    /// The same pin polarity is assumed, and the interrupt must have been enabled with
+    /// [`Self::enable_interrupt`] first.
+    ///
+    /// The pin stays high until the data is read, so the edge is only awaited when the pin is
+    /// low: data which was already waiting is read straight away, rather than missing the edge
+    /// and waiting forever.
+    ///
+    /// This only returns on error: [`Error::Pin`] if the pin can't be read or waited on, or any
+    /// error from reading the data, which is processed as in [`Self::read_all`].
+    pub async fn on_data_ready_async<P: Wait + InputPin>(
+        &mut self,
+        pin: &mut P,
+        mut f: impl FnMut(Measurement),
+    ) -> Result<(), Error<I::Error>> {
+        loop {
+            if !pin.is_high().map_err(|_| Error::Pin)? {
+                pin.wait_for_rising_edge().await.map_err(|_| Error::Pin)?;
+            }
+            f(self.read_all()?);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use embedded_hal::digital::{ErrorKind, ErrorType};
+    use embedded_hal_mock::i2c::{Mock, Transaction};
+
+    const ADDR: u8 = QMC8553L::<Mock>::DEFAULT_ADDR;
+
+    /// Run a future which never actually waits, as none of the mocks do.
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        struct NoopWake;
+        impl std::task::Wake for NoopWake {
+            fn wake(self: std::sync::Arc<Self>) {}
+        }
+
+        let waker = Waker::from(std::sync::Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+        let mut fut = pin!(fut);
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+                return out;
+            }
+        }
+    }
+
+    /// A pin reporting the given levels in turn, then failing; edges are counted.
+    struct TestPin<'a> {
+        levels: &'a [bool],
+        next: core::cell::Cell<usize>,
+        edges: usize,
+    }
+
+    impl ErrorType for TestPin<'_> {
+        type Error = ErrorKind;
+    }
+
+    impl InputPin for TestPin<'_> {
+        fn is_high(&self) -> Result<bool, Self::Error> {
+            let i = self.next.replace(self.next.get() + 1);
+            self.levels.get(i).copied().ok_or(ErrorKind::Other)
+        }
+        fn is_low(&self) -> Result<bool, Self::Error> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    impl Wait for TestPin<'_> {
+        async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+            self.edges += 1;
+            Ok(())
+        }
+        async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    /// Records the total time waited.
+    #[derive(Default)]
+    struct TestDelay(u32);
+
+    impl DelayUs for TestDelay {
+        async fn delay_us(&mut self, us: u32) {
+            self.0 += us;
+        }
+        async fn delay_ms(&mut self, ms: u32) {
+            self.0 += ms * 1000;
+        }
+    }
+
+    #[test]
+    fn wait_ready_polls_status() {
+        let mut i2c = Mock::new(&[
+            Transaction::write_read(ADDR, vec![0x06], vec![0x00]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x00]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+        ]);
+
+        let mut mag = QMC8553L::unconfigured(i2c.clone(), Settings::default(), ADDR);
+        let mut delay = TestDelay::default();
+        assert_eq!(block_on(mag.wait_ready(&mut delay)), Ok(()));
+        assert_eq!(delay.0, 2 * mag.poll_interval_us());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn wait_ready_fixed_rate() {
+        let mut i2c = Mock::new(&[]);
+
+        let mut mag = QMC8553L::unconfigured(i2c.clone(), Settings::default(), ADDR);
+        mag.set_ready_strategy(ReadyStrategy::FixedRate {
+            ticks_per_second: 1000,
+        });
+        let mut delay = TestDelay::default();
+        assert_eq!(block_on(mag.wait_ready(&mut delay)), Ok(()));
+        assert_eq!(delay.0, mag.sample_period_us());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn data_ready_edges() {
+        let mut i2c = Mock::new(&[
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x04, 0x00, 0x05, 0x00, 0x06, 0x00]),
+        ]);
+
+        let mut mag = QMC8553L::unconfigured(i2c.clone(), Settings::default(), ADDR);
+        // Data already waiting, then none
+        let mut pin = TestPin {
+            levels: &[true, false],
+            next: core::cell::Cell::new(0),
+            edges: 0,
+        };
+        let mut seen = Vec::new();
+        assert_eq!(
+            block_on(mag.on_data_ready_async(&mut pin, |m| seen.push(m))),
+            Err(Error::Pin)
+        );
+        assert_eq!(
+            seen,
+            [Measurement::from([1, 2, 3]), Measurement::from([4, 5, 6])]
+        );
+        assert_eq!(pin.edges, 1);
+
+        i2c.done();
+    }
+}
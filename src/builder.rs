@@ -0,0 +1,119 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{settings::Settings, AxisRemap, Error, Frame, HardIron, QMC8553L};
+
+/// Accumulates configuration for a [`QMC8553L`], to be applied all at once.
+///
+/// Create one with [`QMC8553L::builder`]:
+///
+/// ```no_run
+/// # fn main() {
+/// # let mock_i2c = embedded_hal_mock::i2c::Mock::new(&[]);
+/// use qmc5883l::{HardIron, QMC8553L, settings::Settings};
+/// let mag = QMC8553L::builder(mock_i2c)
+///     .settings(Settings::default())
+///     .hard_iron(HardIron { x: 120, y: -45, z: 8 })
+///     .declination(-1.5)
+///     .init()
+///     .unwrap();
+/// # }
+/// ```
+pub struct Builder<I: I2c> {
+    i2c: I,
+    addr: u8,
+    settings: Settings,
+    hard_iron: HardIron,
+    declination: f32,
+    scale: (f32, f32, f32),
+    remap: AxisRemap,
+    frame: Frame,
+}
+
+impl<I: I2c> Builder<I> {
+    pub(crate) fn new(i2c: I) -> Self {
+        Self {
+            i2c,
+            addr: QMC8553L::<I>::DEFAULT_ADDR,
+            settings: Settings::default(),
+            hard_iron: HardIron::default(),
+            declination: 0.0,
+            scale: (1.0, 1.0, 1.0),
+            remap: AxisRemap::IDENTITY,
+            frame: Frame::Raw,
+        }
+    }
+
+    /// Use a non-standard address for the device.
+    ///
+    /// See [`QMC8553L::new_with_address`].
+    #[must_use]
+    pub fn address(mut self, addr: u8) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Set the [`Settings`] to apply.
+    #[must_use]
+    pub fn settings(mut self, set: Settings) -> Self {
+        self.settings = set;
+        self
+    }
+
+    /// Set the hard-iron offsets.
+    ///
+    /// See [`QMC8553L::set_hard_iron`].
+    #[must_use]
+    pub fn hard_iron(mut self, hard_iron: HardIron) -> Self {
+        self.hard_iron = hard_iron;
+        self
+    }
+
+    /// Set the magnetic declination, in degrees.
+    ///
+    /// See [`QMC8553L::set_declination`].
+    #[must_use]
+    pub fn declination(mut self, declination: f32) -> Self {
+        self.declination = declination;
+        self
+    }
+
+    /// Set the per-axis scale factors.
+    ///
+    /// See [`QMC8553L::set_axis_scale`].
+    #[must_use]
+    pub fn axis_scale(mut self, scale: (f32, f32, f32)) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set the axis remapping.
+    ///
+    /// See [`QMC8553L::set_axis_remap`].
+    #[must_use]
+    pub fn axis_remap(mut self, remap: AxisRemap) -> Self {
+        self.remap = remap;
+        self
+    }
+
+    /// Set the output coordinate frame.
+    ///
+    /// See [`QMC8553L::set_output_frame`].
+    #[must_use]
+    pub fn output_frame(mut self, frame: Frame) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Initialise the device with the accumulated configuration.
+    ///
+    /// The device is reset before the settings are applied, as in [`QMC8553L::new`].
+    pub fn init(self) -> Result<QMC8553L<I>, Error<I::Error>> {
+        let mut mag = QMC8553L::new_with_address(self.i2c, self.settings, self.addr)?;
+        mag.set_hard_iron(self.hard_iron);
+        mag.set_declination(self.declination);
+        mag.set_axis_scale(self.scale);
+        mag.set_axis_remap(self.remap);
+        mag.set_output_frame(self.frame);
+        Ok(mag)
+    }
+}
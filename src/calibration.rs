@@ -0,0 +1,551 @@
+use core::array::TryFromSliceError;
+#[cfg(feature = "defmt")]
+use defmt::Format;
+use embedded_hal::i2c::I2c;
+
+use crate::{Measurement, QMC8553L};
+
+/// Hard-iron offsets, in raw counts.
+///
+/// These are the readings the sensor gives in the absence of any external field, caused by
+/// magnetised material near the sensor, and should be subtracted from every reading.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[allow(missing_docs)]
+pub struct HardIron {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+}
+
+impl HardIron {
+    /// Subtract the offsets from a reading, saturating at the bounds of `i16`.
+    #[must_use]
+    pub fn apply(&self, m: Measurement) -> Measurement {
+        m - Measurement::from([self.x, self.y, self.z])
+    }
+}
+
+/// Multiply each axis of a reading by the corresponding factor, rounding to the nearest count and
+/// saturating at the bounds of `i16`.
+pub(crate) fn apply_scale(m: Measurement, (sx, sy, sz): (f32, f32, f32)) -> Measurement {
+    let scale = |val: i16, factor: f32| {
+        let scaled = f32::from(val) * factor;
+        let rounded = if scaled < 0.0 {
+            scaled - 0.5
+        } else {
+            scaled + 0.5
+        };
+        // Float to int casts saturate
+        #[allow(clippy::cast_possible_truncation)]
+        let out = rounded as i16;
+        out
+    };
+    Measurement::from([scale(m.x, sx), scale(m.y, sy), scale(m.z, sz)])
+}
+
+/// Collects the extremes of the field seen on each axis while the sensor is rotated, to estimate
+/// hard-iron offsets and per-axis scale factors.
+///
+/// Feed it readings (from [`crate::QMC8553L::read_all`]) while slowly rotating the device through
+/// as many orientations as possible, then use [`Self::hard_iron`] and [`Self::scale`] to
+/// configure the driver.
+/// [`Self::quality`] gives an indication of whether enough orientations have been covered.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Calibrator {
+    min: [i16; 3],
+    max: [i16; 3],
+    samples: usize,
+    /// A bitmap of the octants of field-direction space which readings have fallen in.
+    octants: u8,
+}
+
+impl Calibrator {
+    /// Create a calibrator with no samples.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            min: [i16::MAX; 3],
+            max: [i16::MIN; 3],
+            samples: 0,
+            octants: 0,
+        }
+    }
+
+    /// Add a reading.
+    pub fn add(&mut self, m: Measurement) {
+        for (i, val) in <[i16; 3]>::from(m).into_iter().enumerate() {
+            self.min[i] = self.min[i].min(val);
+            self.max[i] = self.max[i].max(val);
+        }
+        self.samples += 1;
+
+        // Bucket the direction by which side of the current centre each axis is on
+        let centre = self.hard_iron();
+        let octant = usize::from(m.x >= centre.x)
+            | usize::from(m.y >= centre.y) << 1
+            | usize::from(m.z >= centre.z) << 2;
+        self.octants |= 1 << octant;
+    }
+
+    /// Shrink the extremes seen on each axis towards their centre, by `1 / 2^shift` of the span.
+    ///
+    /// Calling this periodically makes the calibrator gradually forget old readings, so it can
+    /// follow a changing magnetic environment: extremes which are still being reached are soon
+    /// restored by new readings, while stale ones fade away.
+    pub fn decay(&mut self, shift: u32) {
+        if self.samples == 0 {
+            return;
+        }
+        for i in 0..3 {
+            // Half the shrinkage comes off each end
+            let step = self.max[i]
+                .abs_diff(self.min[i])
+                .checked_shr(shift.saturating_add(1))
+                .unwrap_or(0);
+            // At most half the span of two `i16`s, so always fits
+            #[allow(clippy::cast_possible_wrap)]
+            let step = step as i16;
+            self.min[i] = self.min[i].saturating_add(step);
+            self.max[i] = self.max[i].saturating_sub(step);
+        }
+    }
+
+    /// The number of readings added so far.
+    #[must_use]
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// The span between the smallest and largest reading seen on each axis.
+    fn spans(&self) -> [u16; 3] {
+        if self.samples == 0 {
+            return [0; 3];
+        }
+        [0, 1, 2].map(|i| self.max[i].abs_diff(self.min[i]))
+    }
+
+    /// Estimate the hard-iron offsets, as the centre of the range seen on each axis.
+    ///
+    /// Returns zero offsets if no readings have been added.
+    #[must_use]
+    pub fn hard_iron(&self) -> HardIron {
+        if self.samples == 0 {
+            return HardIron::default();
+        }
+        let centre = |i: usize| {
+            let sum = i32::from(self.min[i]) + i32::from(self.max[i]);
+            // The mean of two `i16`s always fits in an `i16`
+            #[allow(clippy::cast_possible_truncation)]
+            let mid = (sum >> 1) as i16;
+            mid
+        };
+        HardIron {
+            x: centre(0),
+            y: centre(1),
+            z: centre(2),
+        }
+    }
+
+    /// Estimate the per-axis scale factors, suitable for [`crate::QMC8553L::set_axis_scale`].
+    ///
+    /// Each axis is scaled so that its span matches the mean span of all three axes.
+    /// Axes with zero span are given a factor of `1.0`.
+    #[must_use]
+    pub fn scale(&self) -> (f32, f32, f32) {
+        let spans = self.spans().map(f32::from);
+        let mean = spans.iter().sum::<f32>() / 3.0;
+        let factor = |span: f32| if span > 0.0 { mean / span } else { 1.0 };
+        (factor(spans[0]), factor(spans[1]), factor(spans[2]))
+    }
+
+    /// Score how well the collected readings cover all orientations, from `0.0` to `1.0`.
+    ///
+    /// If the device has been rotated through every orientation, the readings will cover a
+    /// sphere (or, with gain mismatch, an ellipsoid close to one) and the span of every axis will
+    /// be similar.
+    /// If some orientations were missed, one or more axes will have a much smaller span.
+    /// The score is the ratio of the smallest axis span to the largest, so `1.0` is a perfectly
+    /// spherical spread and `0.0` means an axis saw no variation at all.
+    ///
+    /// As a rough guide, aim for a score above `0.8` before trusting the calibration.
+    #[must_use]
+    pub fn quality(&self) -> f32 {
+        let spans = self.spans();
+        let (Some(&min), Some(&max)) = (spans.iter().min(), spans.iter().max()) else {
+            return 0.0;
+        };
+        if max == 0 {
+            return 0.0;
+        }
+        f32::from(min) / f32::from(max)
+    }
+
+    /// Estimate how much of the sphere of orientations has been sampled, as a percentage.
+    ///
+    /// Each reading is bucketed into one of the eight octants of field-direction space, by which
+    /// side of the current [`Self::hard_iron`] estimate each axis falls on, and this is the
+    /// proportion of octants which have seen a reading (in steps of 12.5%, rounded down).
+    /// Since the centre estimate moves as readings are added, early readings may be bucketed
+    /// wrongly.
+    ///
+    /// This is intended to drive "keep rotating" prompts while calibrating: it's a rough guide,
+    /// not a guarantee of accuracy (see [`Self::quality`]).
+    #[must_use]
+    pub fn coverage(&self) -> u8 {
+        // At most 8 octants, so at most 800
+        #[allow(clippy::cast_possible_truncation)]
+        let percent = (self.octants.count_ones() * 100 / 8) as u8;
+        percent
+    }
+}
+
+impl Default for Calibrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A full set of calibration data for the device, suitable for persisting across boots.
+///
+/// This holds all of the driver's calibration state: use [`Self::capture`] to take it out of
+/// the driver once calibrated, [`Self::to_bytes`] and [`Self::from_bytes`] to store it, and
+/// [`Self::apply_to`] to restore it on boot.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct Calibration {
+    /// Hard-iron offsets.
+    pub hard_iron: HardIron,
+    /// The magnetic declination at the device's location, in degrees.
+    pub declination: f32,
+    /// Per-axis scale factors, in the order X, Y, Z (see
+    /// [`crate::QMC8553L::set_axis_scale`]).
+    pub scale: (f32, f32, f32),
+    /// The sensitivity drift with temperature, in parts per million per °C (see
+    /// [`crate::QMC8553L::set_temp_coefficient`]).
+    ///
+    /// Only used with the `float` feature.
+    pub temp_coefficient: f32,
+    /// The raw temperature at which no temperature correction is applied (see
+    /// [`crate::QMC8553L::set_reference_temp`]).
+    ///
+    /// Only used with the `float` feature.
+    pub reference_temp: i16,
+}
+
+impl Calibration {
+    /// The length of the encoded form of the calibration, in bytes.
+    pub const ENCODED_LEN: usize = 28;
+
+    /// The length of the encoded form used before the scale and temperature compensation were
+    /// included, in bytes.
+    ///
+    /// [`Self::from_bytes`] still accepts this form.
+    pub const LEGACY_ENCODED_LEN: usize = 10;
+
+    /// Take the calibration currently in use out of the driver.
+    ///
+    /// See [`crate::QMC8553L::current_calibration`].
+    pub fn capture<I: I2c>(mag: &QMC8553L<I>) -> Self {
+        mag.current_calibration()
+    }
+
+    /// Apply the calibration to the driver, replacing all of its calibration state.
+    ///
+    /// No bus access is needed.
+    /// Without the `float` feature, the temperature compensation is ignored.
+    pub fn apply_to<I: I2c>(&self, mag: &mut QMC8553L<I>) {
+        mag.hard_iron = self.hard_iron;
+        mag.declination = self.declination;
+        mag.scale = self.scale;
+        #[cfg(feature = "float")]
+        {
+            mag.temp_coefficient = self.temp_coefficient;
+            mag.reference_temp = self.reference_temp;
+        }
+    }
+
+    /// Encode the calibration into a compact byte array.
+    ///
+    /// The layout is stable, and all fields are little-endian:
+    ///
+    /// | Bytes  | Field                              |
+    /// |--------|------------------------------------|
+    /// | 0..2   | Hard-iron X offset (`i16`)         |
+    /// | 2..4   | Hard-iron Y offset (`i16`)         |
+    /// | 4..6   | Hard-iron Z offset (`i16`)         |
+    /// | 6..10  | Declination in degrees (`f32`)     |
+    /// | 10..14 | X scale factor (`f32`)             |
+    /// | 14..18 | Y scale factor (`f32`)             |
+    /// | 18..22 | Z scale factor (`f32`)             |
+    /// | 22..26 | Temperature coefficient (`f32`)    |
+    /// | 26..28 | Reference temperature (`i16`)      |
+    ///
+    /// The first 10 bytes are the same as the legacy layout (see
+    /// [`Self::LEGACY_ENCODED_LEN`]).
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut buf = [0; Self::ENCODED_LEN];
+        buf[0..2].copy_from_slice(&self.hard_iron.x.to_le_bytes());
+        buf[2..4].copy_from_slice(&self.hard_iron.y.to_le_bytes());
+        buf[4..6].copy_from_slice(&self.hard_iron.z.to_le_bytes());
+        buf[6..10].copy_from_slice(&self.declination.to_le_bytes());
+        buf[10..14].copy_from_slice(&self.scale.0.to_le_bytes());
+        buf[14..18].copy_from_slice(&self.scale.1.to_le_bytes());
+        buf[18..22].copy_from_slice(&self.scale.2.to_le_bytes());
+        buf[22..26].copy_from_slice(&self.temp_coefficient.to_le_bytes());
+        buf[26..28].copy_from_slice(&self.reference_temp.to_le_bytes());
+        buf
+    }
+
+    /// Decode a calibration previously encoded with [`Self::to_bytes`].
+    ///
+    /// The legacy form, without the scale and temperature compensation, is also accepted: the
+    /// missing fields take their defaults.
+    /// Fails if `bytes` is not exactly [`Self::ENCODED_LEN`] or [`Self::LEGACY_ENCODED_LEN`]
+    /// bytes long.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TryFromSliceError> {
+        let f32_at =
+            |buf: &[u8], i: usize| f32::from_le_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]);
+        let legacy = |buf: [u8; Self::LEGACY_ENCODED_LEN]| Self {
+            hard_iron: HardIron {
+                x: i16::from_le_bytes([buf[0], buf[1]]),
+                y: i16::from_le_bytes([buf[2], buf[3]]),
+                z: i16::from_le_bytes([buf[4], buf[5]]),
+            },
+            declination: f32_at(&buf, 6),
+            ..Self::default()
+        };
+
+        if let Ok(buf) = bytes.try_into() {
+            return Ok(legacy(buf));
+        }
+        let buf: [u8; Self::ENCODED_LEN] = bytes.try_into()?;
+        // Can't fail, the lengths match
+        let mut cal = legacy(buf[..Self::LEGACY_ENCODED_LEN].try_into()?);
+        cal.scale = (f32_at(&buf, 10), f32_at(&buf, 14), f32_at(&buf, 18));
+        cal.temp_coefficient = f32_at(&buf, 22);
+        cal.reference_temp = i16::from_le_bytes([buf[26], buf[27]]);
+        Ok(cal)
+    }
+}
+
+impl Default for Calibration {
+    /// No correction: zero offsets, declination and temperature coefficient, and unit scale.
+    fn default() -> Self {
+        Self {
+            hard_iron: HardIron::default(),
+            declination: 0.0,
+            scale: (1.0, 1.0, 1.0),
+            temp_coefficient: 0.0,
+            reference_temp: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_round_trip() {
+        let cal = Calibration {
+            hard_iron: HardIron {
+                x: -1234,
+                y: 567,
+                z: i16::MIN,
+            },
+            declination: -3.25,
+            scale: (1.5, 0.75, 1.0),
+            temp_coefficient: -120.0,
+            reference_temp: 2345,
+        };
+
+        assert_eq!(Calibration::from_bytes(&cal.to_bytes()).unwrap(), cal);
+    }
+
+    #[test]
+    fn bytes_layout() {
+        let cal = Calibration {
+            hard_iron: HardIron { x: 1, y: -1, z: 0 },
+            declination: 1.0,
+            reference_temp: -2,
+            ..Calibration::default()
+        };
+
+        assert_eq!(
+            cal.to_bytes(),
+            [
+                0x01, 0x00, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x80, 0x3f,
+                0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x80, 0x3f, 0x00, 0x00, 0x00, 0x00, 0xfe, 0xff
+            ]
+        );
+    }
+
+    #[test]
+    fn legacy_bytes() {
+        let legacy = [0x01, 0x00, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x80, 0x3f];
+        assert_eq!(
+            Calibration::from_bytes(&legacy).unwrap(),
+            Calibration {
+                hard_iron: HardIron { x: 1, y: -1, z: 0 },
+                declination: 1.0,
+                ..Calibration::default()
+            }
+        );
+    }
+
+    #[test]
+    fn wrong_length() {
+        assert!(Calibration::from_bytes(&[0; 9]).is_err());
+        assert!(Calibration::from_bytes(&[0; 11]).is_err());
+        assert!(Calibration::from_bytes(&[0; 27]).is_err());
+        assert!(Calibration::from_bytes(&[0; 29]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn driver_round_trip() {
+        use crate::settings::Settings;
+        use embedded_hal_mock::i2c::{Mock, Transaction};
+
+        const ADDR: u8 = QMC8553L::<Mock>::DEFAULT_ADDR;
+        let data =
+            Transaction::write_read(ADDR, vec![0x00], vec![0x10, 0x01, 0xf0, 0xfe, 0x40, 0x00]);
+        let temp = Transaction::write_read(ADDR, vec![0x07], vec![0x20, 0x0c]);
+        let mut i2c = Mock::new(&[
+            data.clone(),
+            temp.clone(),
+            data.clone(),
+            data.clone(),
+            temp,
+            data,
+        ]);
+
+        let mut calibrated = QMC8553L::unconfigured(i2c.clone(), Settings::default(), ADDR);
+        calibrated.set_hard_iron(HardIron {
+            x: 10,
+            y: -20,
+            z: 5,
+        });
+        calibrated.set_axis_scale((1.25, 0.8, 1.0));
+        calibrated.set_declination(-1.5);
+        calibrated.set_temp_coefficient(500.0);
+        calibrated.set_reference_temp(2000);
+
+        let stored = Calibration::capture(&calibrated).to_bytes();
+        let mut restored = QMC8553L::unconfigured(i2c.clone(), Settings::default(), ADDR);
+        Calibration::from_bytes(&stored)
+            .unwrap()
+            .apply_to(&mut restored);
+        assert_eq!(
+            Calibration::capture(&restored),
+            Calibration::capture(&calibrated)
+        );
+
+        let expected = (
+            calibrated.read_all_corrected().unwrap(),
+            calibrated.read_all_calibrated().unwrap(),
+        );
+        assert_eq!(
+            (
+                restored.read_all_corrected().unwrap(),
+                restored.read_all_calibrated().unwrap()
+            ),
+            expected
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn scale_rounds_and_saturates() {
+        let m = Measurement::from([100, -100, 20_000]);
+        assert_eq!(
+            apply_scale(m, (1.015, 1.015, 2.0)),
+            Measurement::from([102, -102, i16::MAX])
+        );
+        assert_eq!(apply_scale(m, (1.0, 1.0, 1.0)), m);
+    }
+
+    #[test]
+    fn calibrator_estimates() {
+        let mut cal = Calibrator::new();
+        assert!(cal.quality() == 0.0);
+        assert_eq!(cal.hard_iron(), HardIron::default());
+
+        for m in [[-100, 0, 50], [300, -200, 50], [0, 200, -150], [0, 0, 250]] {
+            cal.add(Measurement::from(m));
+        }
+        assert_eq!(cal.samples(), 4);
+        assert_eq!(
+            cal.hard_iron(),
+            HardIron {
+                x: 100,
+                y: 0,
+                z: 50
+            }
+        );
+        assert!((cal.quality() - 1.0).abs() < f32::EPSILON);
+        assert_eq!(cal.scale(), (1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn calibrator_decay() {
+        let mut cal = Calibrator::new();
+        cal.decay(2);
+        assert_eq!(cal.hard_iron(), HardIron::default());
+
+        for m in [[-100, -100, -100], [300, 100, 100]] {
+            cal.add(Measurement::from(m));
+        }
+        // Spans of 400 and 200 shrink by a quarter, keeping the same centre
+        cal.decay(2);
+        assert_eq!(cal.spans(), [300, 150, 150]);
+        assert_eq!(cal.hard_iron(), HardIron { x: 100, y: 0, z: 0 });
+    }
+
+    #[test]
+    fn calibrator_decay_huge_shift() {
+        let mut cal = Calibrator::new();
+        for m in [[-100, -100, -100], [300, 100, 100]] {
+            cal.add(Measurement::from(m));
+        }
+        cal.decay(u32::MAX);
+        assert_eq!(cal.spans(), [400, 200, 200]);
+    }
+
+    #[test]
+    fn calibrator_coverage() {
+        let mut cal = Calibrator::new();
+        assert_eq!(cal.coverage(), 0);
+        // Establish the extremes on each axis, centred on (100, 0, 0)
+        cal.add(Measurement::from([-300, -400, -400]));
+        cal.add(Measurement::from([500, 400, 400]));
+        assert_eq!(cal.coverage(), 12);
+
+        for x in [-200, 400] {
+            for y in [-300, 300] {
+                for z in [-300, 300] {
+                    cal.add(Measurement::from([x, y, z]));
+                }
+            }
+        }
+        assert_eq!(cal.coverage(), 100);
+    }
+
+    #[test]
+    fn calibrator_poor_coverage() {
+        let mut cal = Calibrator::new();
+        // Only rotated about Z
+        for m in [[-400, 0, 10], [400, 0, 10], [0, -400, 30], [0, 400, 30]] {
+            cal.add(Measurement::from(m));
+        }
+        assert!((cal.quality() - 0.025).abs() < 0.001);
+        let (sx, sy, sz) = cal.scale();
+        assert!((sx - 0.675).abs() < 0.001);
+        assert!((sy - 0.675).abs() < 0.001);
+        assert!((sz - 27.0).abs() < 0.001);
+    }
+}
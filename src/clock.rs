@@ -0,0 +1,9 @@
+/// A monotonic time source, used to timestamp readings.
+///
+/// Implement this for whatever timer your platform provides (eg. an RTIC monotonic, an embassy
+/// `Instant`, or a simple tick counter) to use [`crate::QMC8553L::read_stamped`].
+/// The units of the tick are up to the implementation.
+pub trait Clock {
+    /// Get the current tick, which must never decrease.
+    fn now(&self) -> u64;
+}
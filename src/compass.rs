@@ -0,0 +1,59 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Error, HardIron, QMC8553L};
+
+/// A ready-to-use compass, bundling a configured magnetometer with its calibration.
+///
+/// This is a convenience for the common case of just wanting a bearing: the calibration and
+/// heading calculations are applied internally.
+/// For anything more involved, use the [`QMC8553L`] directly, eg. through [`Self::inner`].
+///
+/// ```no_run
+/// # fn main() {
+/// # let mock_i2c = embedded_hal_mock::i2c::Mock::new(&[]);
+/// use qmc5883l::{Compass, HardIron, QMC8553L, settings::Settings};
+/// let mag = QMC8553L::new(mock_i2c, Settings::default()).unwrap();
+/// let mut compass = Compass::new(mag, HardIron { x: 120, y: -45, z: 8 }, (1.0, 0.98, 1.02), -1.5);
+/// println!("Bearing: {}°", compass.bearing_degrees().unwrap());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Compass<I: I2c> {
+    mag: QMC8553L<I>,
+}
+
+impl<I: I2c> Compass<I> {
+    /// Wrap a magnetometer, applying the given hard-iron offsets, per-axis scale factors and
+    /// declination (in degrees, positive east).
+    ///
+    /// See [`QMC8553L::set_hard_iron`], [`QMC8553L::set_axis_scale`] and
+    /// [`QMC8553L::set_declination`].
+    pub fn new(
+        mut mag: QMC8553L<I>,
+        hard_iron: HardIron,
+        scale: (f32, f32, f32),
+        declination: f32,
+    ) -> Self {
+        mag.set_hard_iron(hard_iron);
+        mag.set_axis_scale(scale);
+        mag.set_declination(declination);
+        Self { mag }
+    }
+
+    /// Read the bearing relative to true north, in degrees in the range `[0, 360)`.
+    ///
+    /// The device is assumed to be held level: see [`QMC8553L::heading`].
+    pub fn bearing_degrees(&mut self) -> Result<f32, Error<I::Error>> {
+        self.mag.heading()
+    }
+
+    /// Get the wrapped magnetometer, eg. to change its settings.
+    pub fn inner(&mut self) -> &mut QMC8553L<I> {
+        &mut self.mag
+    }
+
+    /// Release the wrapped magnetometer.
+    pub fn release(self) -> QMC8553L<I> {
+        self.mag
+    }
+}
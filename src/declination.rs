@@ -0,0 +1,111 @@
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+/// The highest degree of the field model.
+const DEGREE: usize = 4;
+
+/// Gauss coefficients `(n, m, g, h)` of the IGRF-13 main field at epoch 2020.0, in nT, truncated
+/// to degree 4.
+#[rustfmt::skip]
+const COEFFS: [(usize, usize, f32, f32); 14] = [
+    (1, 0, -29404.8, 0.0), (1, 1, -1450.9, 4652.5),
+    (2, 0, -2499.6, 0.0), (2, 1, 2982.0, -2991.6), (2, 2, 1677.0, -734.6),
+    (3, 0, 1363.2, 0.0), (3, 1, -2381.2, -82.1), (3, 2, 1236.2, 241.9), (3, 3, 525.7, -543.4),
+    (4, 0, 903.1, 0.0), (4, 1, 809.4, 282.0), (4, 2, 86.2, -158.4), (4, 3, -309.4, 199.8),
+    (4, 4, 47.9, -350.1),
+];
+
+/// The latitude, in degrees, beyond which positions are treated as being at this latitude.
+///
+/// Declination is undefined at the geographic poles.
+const MAX_LATITUDE: f32 = 89.9;
+
+/// Estimate the magnetic declination at a position, in degrees (positive east), for use with
+/// [`crate::QMC8553L::set_declination`].
+///
+/// `lat` and `lon` are in degrees, positive north and east respectively.
+///
+/// This evaluates a coarse model of the Earth's main field (the first four degrees of IGRF-13,
+/// epoch 2020.0) at sea level, so needs no lookup tables or network access.
+/// It's intended for hobby compasses, not navigation: away from the polar regions it's usually
+/// within 3° of the full model, but errors can be larger near the poles and magnetic anomalies.
+/// The field also drifts over time, typically by 0.1° to 0.2° per year, so the estimate becomes
+/// less accurate the further from 2020 it's used.
+#[must_use]
+pub fn declination_for(lat: f32, lon: f32) -> f32 {
+    let colatitude = (90.0 - lat.clamp(-MAX_LATITUDE, MAX_LATITUDE)).to_radians();
+    let lon = lon.to_radians();
+    let (sin_t, cos_t) = (colatitude.sin(), colatitude.cos());
+
+    // Schmidt semi-normalised associated Legendre functions and their derivatives with respect
+    // to colatitude, built up by recurrence
+    let mut p = [[0.0f32; DEGREE + 1]; DEGREE + 1];
+    let mut dp = [[0.0f32; DEGREE + 1]; DEGREE + 1];
+    p[0][0] = 1.0;
+    for n in 1..=DEGREE {
+        // At most 4, so exact as a float
+        #[allow(clippy::cast_precision_loss)]
+        let nf = n as f32;
+        for m in 0..=n {
+            #[allow(clippy::cast_precision_loss)]
+            let mf = m as f32;
+            if m == n {
+                let k = if n == 1 {
+                    1.0
+                } else {
+                    ((2.0 * nf - 1.0) / (2.0 * nf)).sqrt()
+                };
+                p[n][n] = k * sin_t * p[n - 1][n - 1];
+                dp[n][n] = k * (sin_t * dp[n - 1][n - 1] + cos_t * p[n - 1][n - 1]);
+            } else {
+                let (p2, dp2) = if n >= 2 {
+                    (p[n - 2][m], dp[n - 2][m])
+                } else {
+                    (0.0, 0.0)
+                };
+                let a = ((nf - 1.0) * (nf - 1.0) - mf * mf).sqrt();
+                let b = (nf * nf - mf * mf).sqrt();
+                p[n][m] = ((2.0 * nf - 1.0) * cos_t * p[n - 1][m] - a * p2) / b;
+                dp[n][m] =
+                    ((2.0 * nf - 1.0) * (cos_t * dp[n - 1][m] - sin_t * p[n - 1][m]) - a * dp2) / b;
+            }
+        }
+    }
+
+    // The north and east components of the field
+    let (mut north, mut east) = (0.0, 0.0);
+    for (n, m, g, h) in COEFFS {
+        #[allow(clippy::cast_precision_loss)]
+        let mf = m as f32;
+        let (sin_m, cos_m) = ((mf * lon).sin(), (mf * lon).cos());
+        north += (g * cos_m + h * sin_m) * dp[n][m];
+        east += mf * (g * sin_m - h * cos_m) * p[n][m] / sin_t;
+    }
+    east.atan2(north).to_degrees()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_locations() {
+        // Values from evaluating the same truncated model independently
+        for (lat, lon, expected) in [
+            (51.5, -0.1, 0.9),
+            (-33.9, 18.4, -24.8),
+            (-33.9, 151.2, 13.9),
+            (-23.5, -46.6, -22.2),
+            (55.75, 37.6, 12.0),
+        ] {
+            let dec = declination_for(lat, lon);
+            assert!((dec - expected).abs() < 0.2, "{dec} != {expected}");
+        }
+    }
+
+    #[test]
+    fn poles_are_finite() {
+        assert!(declination_for(90.0, 0.0).is_finite());
+        assert!(declination_for(-90.0, 45.0).is_finite());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::Measurement;
+
+/// Averages every `N` readings into one, for logging at a lower rate than the sensor is run at.
+///
+/// ```
+/// use qmc5883l::{Downsampler, Measurement};
+/// let mut down = Downsampler::<4>::new();
+/// for val in [1, 2, 3] {
+///     assert_eq!(down.push(Measurement::from([val; 3])), None);
+/// }
+/// assert_eq!(down.push(Measurement::from([6; 3])), Some(Measurement::from([3; 3])));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Downsampler<const N: usize> {
+    sums: [i64; 3],
+    count: usize,
+}
+
+impl<const N: usize> Downsampler<N> {
+    // Referenced in `new` so that `N == 0` fails to compile
+    const NON_ZERO: () = assert!(N > 0, "Downsampler must average at least one reading");
+
+    /// Create a downsampler with no readings.
+    #[must_use]
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::NON_ZERO;
+        Self {
+            sums: [0; 3],
+            count: 0,
+        }
+    }
+
+    /// Add a reading, returning the mean of the last `N` readings if this completes a set.
+    ///
+    /// The mean is rounded towards zero.
+    /// The downsampler is reset after a mean is returned.
+    pub fn push(&mut self, m: Measurement) -> Option<Measurement> {
+        for (sum, val) in self.sums.iter_mut().zip(<[i16; 3]>::from(m)) {
+            *sum += i64::from(val);
+        }
+        self.count += 1;
+        if self.count < N {
+            return None;
+        }
+
+        // An `i64` sum of `i16`s can't overflow before `N` reaches 2^48, so nor will `N` wrap
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        let count = self.count as i64;
+        // The mean of `i16`s always fits in an `i16`
+        #[allow(clippy::cast_possible_truncation)]
+        let mean = self.sums.map(|sum| (sum / count) as i16);
+        self.reset();
+        Some(Measurement::from(mean))
+    }
+
+    /// Discard any readings collected since the last mean.
+    pub fn reset(&mut self) {
+        self.sums = [0; 3];
+        self.count = 0;
+    }
+}
+
+impl<const N: usize> Default for Downsampler<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_sequence() {
+        let mut down = Downsampler::<3>::new();
+        let inputs = [
+            [10, -10, 0],
+            [20, -20, 1],
+            [31, -31, 1],
+            [i16::MAX, i16::MIN, 5],
+            [i16::MAX, i16::MIN, 5],
+            [i16::MAX, i16::MIN, 6],
+        ];
+        let outputs: Vec<_> = inputs
+            .into_iter()
+            .filter_map(|m| down.push(Measurement::from(m)))
+            .collect();
+
+        assert_eq!(
+            outputs,
+            [
+                Measurement::from([20, -20, 0]),
+                Measurement::from([i16::MAX, i16::MIN, 5]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reset_discards() {
+        let mut down = Downsampler::<2>::new();
+        assert_eq!(down.push(Measurement::from([100; 3])), None);
+        down.reset();
+        assert_eq!(down.push(Measurement::from([2; 3])), None);
+        assert_eq!(
+            down.push(Measurement::from([4; 3])),
+            Some(Measurement::from([3; 3]))
+        );
+    }
+
+    #[test]
+    fn large_windows_dont_overflow() {
+        const N: usize = 65_537;
+        let mut down = Downsampler::<N>::new();
+        let extreme = Measurement::from([i16::MIN, i16::MAX, i16::MIN]);
+        for _ in 1..N {
+            assert_eq!(down.push(extreme), None);
+        }
+        assert_eq!(down.push(extreme), Some(extreme));
+    }
+}
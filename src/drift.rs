@@ -0,0 +1,115 @@
+use crate::Measurement;
+
+/// Tracks a slowly-adapting baseline of the field, reporting how far each reading deviates from
+/// it.
+///
+/// This suits magnet-presence detection where the environment drifts slowly (eg. with
+/// temperature): the baseline follows the drift, while a magnet arriving makes the deviation
+/// jump.
+/// Unlike [`crate::QMC8553L::zero`], which captures a fixed baseline, this keeps adapting.
+/// The baseline is an exponentially-weighted moving average in fixed point, so only integer
+/// arithmetic is used.
+///
+/// ```
+/// use qmc5883l::{DriftBaseline, Measurement};
+/// let mut baseline = DriftBaseline::new(6);
+/// baseline.update(Measurement::from([100, 0, 0]));
+/// // A sudden change shows up in full
+/// assert_eq!(baseline.update(Measurement::from([600, 0, 0])), Measurement::from([500, 0, 0]));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DriftBaseline {
+    /// The baseline, with [`Self::FRAC_BITS`] fractional bits.
+    baseline: Option<[i32; 3]>,
+    shift: u8,
+}
+
+impl DriftBaseline {
+    /// The number of fractional bits the baseline is kept to, so that slow drift still moves it.
+    const FRAC_BITS: u8 = 8;
+
+    /// Create a baseline which moves `1 / 2^shift` of the way towards each new reading.
+    ///
+    /// A larger `shift` adapts more slowly: the baseline takes roughly `2^shift` readings to
+    /// follow a change, so pick it so that `2^shift` sample periods is much longer than the
+    /// events to detect, but shorter than the drift to ignore.
+    /// For example, at 10Hz a `shift` of `8` adapts over roughly 25 seconds.
+    /// `shift` is capped at 16.
+    #[must_use]
+    pub fn new(shift: u8) -> Self {
+        Self {
+            baseline: None,
+            shift: shift.min(16),
+        }
+    }
+
+    /// Add a reading, returning its deviation from the baseline before the baseline adapts to
+    /// it.
+    ///
+    /// The first reading becomes the baseline, so gives a zero deviation.
+    /// The deviation saturates at the bounds of `i16`.
+    pub fn update(&mut self, m: Measurement) -> Measurement {
+        let scaled = <[i16; 3]>::from(m).map(|val| i32::from(val) << Self::FRAC_BITS);
+        let baseline = self.baseline.get_or_insert(scaled);
+        let deviation = m - Self::whole(*baseline);
+
+        for (base, val) in baseline.iter_mut().zip(scaled) {
+            *base += (val - *base) >> self.shift;
+        }
+        deviation
+    }
+
+    /// Get the current baseline, rounded to the nearest count.
+    ///
+    /// Returns `None` before any readings have been added.
+    #[must_use]
+    pub fn baseline(&self) -> Option<Measurement> {
+        self.baseline.map(Self::whole)
+    }
+
+    /// Round a fixed-point baseline to whole counts.
+    fn whole(base: [i32; 3]) -> Measurement {
+        let half = 1 << (Self::FRAC_BITS - 1);
+        // The baseline is an average of `i16`s, so always fits
+        #[allow(clippy::cast_possible_truncation)]
+        let rounded = base.map(|val| ((val + half) >> Self::FRAC_BITS) as i16);
+        Measurement::from(rounded)
+    }
+
+    /// Forget the baseline, so the next reading starts a new one.
+    pub fn reset(&mut self) {
+        self.baseline = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_slow_drift() {
+        let mut baseline = DriftBaseline::new(2);
+        assert_eq!(baseline.baseline(), None);
+        assert_eq!(
+            baseline.update(Measurement::from([100, -100, 0])),
+            Measurement::default()
+        );
+
+        // Each step moves a quarter of the way towards the new reading
+        assert_eq!(
+            baseline.update(Measurement::from([200, -100, 0])),
+            Measurement::from([100, 0, 0])
+        );
+        assert_eq!(baseline.baseline(), Some(Measurement::from([125, -100, 0])));
+        for _ in 0..50 {
+            baseline.update(Measurement::from([200, -100, 0]));
+        }
+        assert_eq!(
+            baseline.update(Measurement::from([200, -100, 0])),
+            Measurement::default()
+        );
+
+        baseline.reset();
+        assert_eq!(baseline.baseline(), None);
+    }
+}
@@ -0,0 +1,164 @@
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+use crate::Measurement;
+
+/// A source of tilt information, such as an accelerometer.
+///
+/// Implement this for your IMU to use [`crate::QMC8553L::heading_with_tilt`].
+///
+/// Angles are in radians, in the North-East-Down convention used by the heading calculation:
+/// X points forwards, Y to the right and Z downwards.
+/// This isn't the sensor's own frame, whose Z axis points up out of the package (see
+/// [`crate::Frame`]): readings are converted to North-East-Down before the heading is
+/// calculated, assuming the sensor is mounted with its X axis pointing forwards.
+/// `src` must follow North-East-Down, whatever output frame the driver is set to.
+pub trait TiltSource {
+    /// The pitch of the device, in radians: positive when the nose (X axis) is raised.
+    fn pitch(&self) -> f32;
+    /// The roll of the device, in radians: positive when the right side (Y axis) is lowered.
+    fn roll(&self) -> f32;
+}
+
+/// Calculate a tilt-compensated heading in degrees, in the range `[0, 360)`.
+///
+/// `m` must be in the North-East-Down frame (see [`TiltSource`]).
+/// This rotates the field vector back into the horizontal plane before taking the heading.
+pub(crate) fn tilt_compensated(m: Measurement, pitch: f32, roll: f32) -> f32 {
+    let (x, y, z) = (f32::from(m.x), f32::from(m.y), f32::from(m.z));
+    let (sin_p, cos_p) = (pitch.sin(), pitch.cos());
+    let (sin_r, cos_r) = (roll.sin(), roll.cos());
+
+    let horiz_x = x * cos_p + y * sin_p * sin_r + z * sin_p * cos_r;
+    let horiz_y = y * cos_r - z * sin_r;
+
+    normalise_degrees((-horiz_y).atan2(horiz_x).to_degrees())
+}
+
+/// Calculate the inclination (dip) of the field in degrees, in the range `[-90, 90]`: the
+/// angle of the field below the sensor's horizontal plane.
+///
+/// `m` must be in the North-East-Down frame, so this is positive when the field points down,
+/// as in the northern hemisphere.
+pub(crate) fn inclination(m: Measurement) -> f32 {
+    let (x, y, z) = (f32::from(m.x), f32::from(m.y), f32::from(m.z));
+    z.atan2((x * x + y * y).sqrt()).to_degrees()
+}
+
+/// One of the eight principal compass directions.
+///
+/// Each direction covers a 45° sector of headings, centred on the direction itself: eg.
+/// [`Cardinal::N`] covers `[337.5, 22.5)`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+#[allow(missing_docs)]
+pub enum Cardinal {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Cardinal {
+    /// The width of the sector of headings covered by each direction, in degrees.
+    pub const SECTOR_WIDTH: f32 = 45.0;
+
+    const ALL: [Self; 8] = [
+        Self::N,
+        Self::NE,
+        Self::E,
+        Self::SE,
+        Self::S,
+        Self::SW,
+        Self::W,
+        Self::NW,
+    ];
+
+    /// Get the direction whose sector contains `heading`, in degrees.
+    #[must_use]
+    pub fn from_heading(heading: f32) -> Self {
+        let shifted = normalise_degrees(heading + Self::SECTOR_WIDTH / 2.0);
+        // `shifted` is in `[0, 360)`, so the index is in `[0, 8)`
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let idx = (shifted / Self::SECTOR_WIDTH) as usize;
+        Self::ALL[idx % Self::ALL.len()]
+    }
+
+    /// The heading at the centre of this direction's sector, in degrees.
+    #[must_use]
+    pub fn centre(self) -> f32 {
+        f32::from(self as u8) * Self::SECTOR_WIDTH
+    }
+}
+
+/// The smallest angle between two headings, in degrees, in the range `[0, 180]`.
+pub(crate) fn angle_between(a: f32, b: f32) -> f32 {
+    let diff = normalise_degrees(a - b);
+    if diff > 180.0 {
+        360.0 - diff
+    } else {
+        diff
+    }
+}
+
+/// Wrap an angle in degrees into the range `[0, 360)`.
+pub(crate) fn normalise_degrees(deg: f32) -> f32 {
+    let wrapped = deg % 360.0;
+    if wrapped < 0.0 {
+        wrapped + 360.0
+    } else {
+        wrapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.01
+    }
+
+    #[test]
+    fn inclination_angles() {
+        assert!(close(inclination(Measurement::from([100, 0, 100])), 45.0));
+        assert!(close(inclination(Measurement::from([0, 100, -100])), -45.0));
+        assert!(close(inclination(Measurement::from([0, 0, 100])), 90.0));
+        assert!(close(inclination(Measurement::from([100, 100, 0])), 0.0));
+    }
+
+    #[test]
+    fn level_matches_flat_heading() {
+        // Field pointing along X is north
+        let north = Measurement::from([1000, 0, 500]);
+        assert!(close(tilt_compensated(north, 0.0, 0.0), 0.0));
+        // Field pointing along -Y means we're facing east
+        let east = Measurement::from([0, -1000, 500]);
+        assert!(close(tilt_compensated(east, 0.0, 0.0), 90.0));
+    }
+
+    #[test]
+    fn rolled_by_half_turn() {
+        // Upside down about X: Y and Z are inverted
+        let m = Measurement::from([0, 1000, -500]);
+        assert!(close(tilt_compensated(m, 0.0, core::f32::consts::PI), 90.0));
+    }
+
+    #[test]
+    fn cardinal_sectors() {
+        assert_eq!(Cardinal::from_heading(0.0), Cardinal::N);
+        assert_eq!(Cardinal::from_heading(350.0), Cardinal::N);
+        assert_eq!(Cardinal::from_heading(22.4), Cardinal::N);
+        assert_eq!(Cardinal::from_heading(22.6), Cardinal::NE);
+        assert_eq!(Cardinal::from_heading(180.0), Cardinal::S);
+        assert_eq!(Cardinal::from_heading(-90.0), Cardinal::W);
+        assert!(close(Cardinal::NW.centre(), 315.0));
+        assert!(close(angle_between(350.0, 10.0), 20.0));
+        assert!(close(angle_between(10.0, 350.0), 20.0));
+    }
+}
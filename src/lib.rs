@@ -1,29 +1,114 @@
 //! Control and read from the QMC8553L magnetometer.
 //!
 //! To get started, take a look at [`QMC8553L::new`].
-#![no_std]
+//!
+//! # Logging
+//!
+//! With the `defmt` feature enabled, the driver's types implement `defmt::Format`, and the
+//! driver logs its own diagnostic messages.
+//! The messages are controlled by the `log` feature, which is on by default: to use `defmt` in
+//! your application without the driver's messages, disable the default features and enable only
+//! `defmt`.
+//!
+//! For debugging protocol issues, the `trace` feature additionally logs every byte read from or
+//! written to the device with `defmt::trace!`, which is otherwise compiled out entirely.
+//! This formats a message for every transaction, so it noticeably slows down bus-heavy code
+//! (eg. explicit reads) and bloats the binary: only enable it while debugging.
+//!
+//! # Default settings
+//!
+//! Projects with a fixed configuration can change what [`settings::Settings::default`] returns
+//! with the `default-*` features, rather than spelling the settings out at every call site:
+//!
+//! - Output data rate: `default-50hz`, `default-100hz` or `default-200hz` (otherwise 10Hz)
+//! - Oversampling: `default-osr512`, `default-osr256` or `default-osr128` (otherwise 64x)
+//! - Range: `default-8g` (otherwise ±2 Gauss)
+//!
+//! Since features are additive, another crate in the dependency graph may enable a different
+//! option from the same group.
+//! In that case, the fastest rate and the highest oversampling enabled take precedence.
+//! The resulting combination must still be one the device supports (see
+//! [`settings::Settings::is_supported`]), or the constructors will return
+//! [`Error::UnsupportedCombo`].
+#![cfg_attr(not(test), no_std)]
 #![forbid(unsafe_code)]
 #![deny(clippy::pedantic)]
 #![allow(clippy::missing_errors_doc)]
 #![warn(missing_docs)]
 
-// TODO: SET/RESET register access
-// TODO: interrupts
-// TODO: testing
-
+mod anomaly;
+#[cfg(feature = "async")]
+mod asynch;
+mod builder;
+mod calibration;
+mod clock;
+#[cfg(feature = "float")]
+mod compass;
+#[cfg(feature = "wmm-lite")]
+mod declination;
+mod downsample;
+mod drift;
+#[cfg(feature = "float")]
+mod heading;
+#[cfg(feature = "float")]
+mod matrix;
+mod measurement;
+mod mux;
 mod registers;
+mod ring;
+#[cfg(feature = "sensor-trait")]
+mod sensor;
+mod temperature;
 
-#[cfg(feature = "defmt")]
+pub use anomaly::AnomalyDetector;
+pub use builder::Builder;
+pub use calibration::{Calibration, Calibrator, HardIron};
+pub use clock::Clock;
+#[cfg(feature = "float")]
+pub use compass::Compass;
+use core::fmt;
+#[cfg(feature = "wmm-lite")]
+pub use declination::declination_for;
+#[cfg(all(feature = "defmt", feature = "log"))]
 use defmt::{debug, info, warn};
+pub use downsample::Downsampler;
+pub use drift::DriftBaseline;
+use embedded_hal::delay::DelayUs;
+use embedded_hal::digital::InputPin;
 use embedded_hal::i2c::I2c;
+#[cfg(feature = "float")]
+pub use heading::{Cardinal, TiltSource};
+#[cfg(feature = "float")]
+pub use matrix::Matrix3;
+pub use measurement::{AxisRemap, Frame, Measurement};
+pub use mux::MuxedI2c;
 use registers::Registers;
+pub use registers::{
+    decode_i16_be, decode_i16_le, ByteOrder, Control2, Control2Config, RegisterAddr, Status,
+};
+pub use ring::RingSampler;
+#[cfg(feature = "sensor-trait")]
+pub use sensor::MagnetometerReading;
 use settings::Settings;
+pub use temperature::TempMonitor;
+
+/// Re-exports of the most commonly used types.
+///
+/// ```
+/// use qmc5883l::prelude::*;
+/// ```
+pub mod prelude {
+    pub use crate::settings::{FullScale, OutputDataRate, OverSampleRatio, Settings};
+    pub use crate::{Axis, Error, Measurement, QMC8553L};
+}
 
 /// Settings for the device.
 pub mod settings {
     #[cfg(feature = "defmt")]
     use defmt::Format;
     use enumn::N;
+    #[cfg(feature = "ufmt")]
+    use ufmt::derive::uDebug;
 
     // TODO: review defaults
 
@@ -32,6 +117,8 @@ pub mod settings {
     /// Controls the frequency at which reads can be made.
     #[derive(Copy, Clone, Debug, PartialEq, Eq, Default, N)]
     #[cfg_attr(feature = "defmt", derive(Format))]
+    #[cfg_attr(feature = "ufmt", derive(uDebug))]
+    #[repr(u8)]
     pub enum OutputDataRate {
         /// 10Hz
         #[default]
@@ -44,10 +131,41 @@ pub mod settings {
         OSR200 = 0b11,
     }
 
+    impl OutputDataRate {
+        /// The rate, in Hz.
+        #[must_use]
+        pub fn hz(self) -> u32 {
+            match self {
+                Self::OSR10 => 10,
+                Self::OSR50 => 50,
+                Self::OSR100 => 100,
+                Self::OSR200 => 200,
+            }
+        }
+
+        /// The rate as a [`fugit::HertzU32`], so the unit is carried in the type.
+        ///
+        /// See [`Self::hz`] for a plain integer.
+        #[must_use]
+        pub fn rate(self) -> fugit::HertzU32 {
+            fugit::HertzU32::from_raw(self.hz())
+        }
+
+        /// Get the output data rate matching `rate` exactly, if there is one.
+        #[must_use]
+        pub fn from_rate(rate: fugit::HertzU32) -> Option<Self> {
+            [Self::OSR10, Self::OSR50, Self::OSR100, Self::OSR200]
+                .into_iter()
+                .find(|odr| odr.hz() == rate.raw())
+        }
+    }
+
     /// The Oversample Ratio of the device.
     #[derive(Copy, Clone, Debug, PartialEq, Eq, Default, N)]
     #[cfg_attr(feature = "defmt", derive(Format))]
+    #[cfg_attr(feature = "ufmt", derive(uDebug))]
     #[allow(missing_docs)]
+    #[repr(u8)]
     pub enum OverSampleRatio {
         OSR512 = 0b00,
         OSR256 = 0b01,
@@ -56,45 +174,467 @@ pub mod settings {
         OSR64 = 0b11,
     }
 
+    impl OverSampleRatio {
+        /// The number of samples taken per measurement.
+        #[must_use]
+        pub fn ratio(self) -> u16 {
+            match self {
+                Self::OSR512 => 512,
+                Self::OSR256 => 256,
+                Self::OSR128 => 128,
+                Self::OSR64 => 64,
+            }
+        }
+
+        /// The number of samples taken per measurement, as [`Oversamples`] so it can't be
+        /// mistaken for a rate.
+        ///
+        /// See [`Self::ratio`] for a plain integer.
+        #[must_use]
+        pub fn oversamples(self) -> Oversamples {
+            Oversamples(self.ratio())
+        }
+
+        /// Get the oversample ratio matching `oversamples` exactly, if there is one.
+        #[must_use]
+        pub fn from_oversamples(oversamples: Oversamples) -> Option<Self> {
+            [Self::OSR512, Self::OSR256, Self::OSR128, Self::OSR64]
+                .into_iter()
+                .find(|osr| osr.ratio() == oversamples.0)
+        }
+    }
+
+    /// A number of samples taken per measurement: see [`OverSampleRatio::oversamples`].
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    #[cfg_attr(feature = "defmt", derive(Format))]
+    #[cfg_attr(feature = "ufmt", derive(uDebug))]
+    pub struct Oversamples(pub u16);
+
+    /// The Full Scale range of the device.
     #[derive(Copy, Clone, Debug, PartialEq, Eq, Default, N)]
     #[cfg_attr(feature = "defmt", derive(Format))]
+    #[cfg_attr(feature = "ufmt", derive(uDebug))]
+    #[repr(u8)]
     pub enum FullScale {
+        /// ±2 Gauss
         #[default]
         RNG2G = 0b00,
+        /// ±8 Gauss
         RNG8G = 0b01,
     }
 
+    impl FullScale {
+        /// The largest field strength measurable in this range, in Gauss.
+        #[must_use]
+        pub fn max_field_gauss(self) -> f32 {
+            match self {
+                Self::RNG2G => 2.0,
+                Self::RNG8G => 8.0,
+            }
+        }
+
+        /// The sensitivity of the device in this range, in counts (LSB) per Gauss.
+        #[must_use]
+        pub fn scale_factor(self) -> f32 {
+            f32::from(self.counts_per_gauss())
+        }
+
+        /// The sensitivity of the device in this range, in counts (LSB) per Gauss, as an integer.
+        #[must_use]
+        pub fn counts_per_gauss(self) -> u16 {
+            match self {
+                Self::RNG2G => 12_000,
+                Self::RNG8G => 3_000,
+            }
+        }
+
+        /// The largest reading the device is rated for in this range, in counts.
+        #[must_use]
+        pub fn max_counts(self) -> i16 {
+            // Both ranges happen to come to the same count, but spell out where it comes from
+            #[allow(clippy::match_same_arms)]
+            match self {
+                Self::RNG2G => 2 * 12_000,
+                Self::RNG8G => 8 * 3_000,
+            }
+        }
+
+        /// The resolution of the device in this range: the field strength represented by one
+        /// count (LSB), in Gauss.
+        ///
+        /// This is the inverse of [`Self::scale_factor`]: the smaller range has a finer
+        /// resolution, since the same number of counts covers a smaller span of field.
+        #[must_use]
+        pub fn resolution_gauss(self) -> f32 {
+            1.0 / self.scale_factor()
+        }
+    }
+
     #[allow(missing_docs)]
-    #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
     #[cfg_attr(feature = "defmt", derive(Format))]
+    #[cfg_attr(feature = "ufmt", derive(uDebug))]
     pub struct Settings {
         pub odr: OutputDataRate,
         pub osr: OverSampleRatio,
         pub rng: FullScale,
     }
 
+    impl Default for Settings {
+        /// Get [`Settings::DEFAULT`], which can be changed with the `default-*` features (see the
+        /// crate documentation).
+        fn default() -> Self {
+            Self::DEFAULT
+        }
+    }
+
+    /// The measurement mode of the device.
+    ///
+    /// This is stored in the low bits of the same register as the [`Settings`], but is managed by
+    /// the driver (see [`crate::QMC8553L::to_standby`] and [`crate::QMC8553L::wake`]).
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "defmt", derive(Format))]
+    #[cfg_attr(feature = "ufmt", derive(uDebug))]
+    pub enum Mode {
+        /// Standby: no measurements are being taken.
+        Standby,
+        /// Continuous measurement at the configured [`OutputDataRate`].
+        Continuous,
+        /// One of the values reserved by the datasheet.
+        Reserved,
+    }
+
+    impl From<u8> for Mode {
+        /// Decode the mode from a value of the settings register.
+        fn from(val: u8) -> Self {
+            match val & Settings::MODE_MASK {
+                0b00 => Self::Standby,
+                0b01 => Self::Continuous,
+                _ => Self::Reserved,
+            }
+        }
+    }
+
     impl Settings {
         pub(crate) const ADDR: u8 = 0x09;
+        /// The bits of the settings register which hold the [`Mode`].
+        pub(crate) const MODE_MASK: u8 = 0b0000_0011;
+
+        /// The default [`Settings`].
+        ///
+        /// Without any `default-*` features, this is 10Hz, 64x oversampling and ±2 Gauss: the
+        /// defaults of the individual fields.
+        pub const DEFAULT: Settings = Settings {
+            odr: if cfg!(feature = "default-200hz") {
+                OutputDataRate::OSR200
+            } else if cfg!(feature = "default-100hz") {
+                OutputDataRate::OSR100
+            } else if cfg!(feature = "default-50hz") {
+                OutputDataRate::OSR50
+            } else {
+                OutputDataRate::OSR10
+            },
+            osr: if cfg!(feature = "default-osr512") {
+                OverSampleRatio::OSR512
+            } else if cfg!(feature = "default-osr256") {
+                OverSampleRatio::OSR256
+            } else if cfg!(feature = "default-osr128") {
+                OverSampleRatio::OSR128
+            } else {
+                OverSampleRatio::OSR64
+            },
+            rng: if cfg!(feature = "default-8g") {
+                FullScale::RNG8G
+            } else {
+                FullScale::RNG2G
+            },
+        };
+
+        /// The settings register value for the default [`Settings`], in continuous mode.
+        ///
+        /// This is the value written by `u8::from(Settings::default())`, available at compile
+        /// time for minimal code paths which write the register directly.
+        pub const DEFAULT_RAW: u8 = Self::DEFAULT.to_raw(Mode::Continuous);
+
+        /// Estimate the current drawn by the device in continuous measurement mode, in µA.
+        ///
+        /// These are nominal figures based on the datasheet (3µA in standby, 75µA at 10Hz with
+        /// 512x oversampling), scaled linearly with the number of conversions per second.
+        /// They are not measured values, but can be used to compare settings for a power budget.
+        #[must_use]
+        pub fn estimated_current_ua(&self) -> u32 {
+            const STANDBY_UA: u32 = 3;
+            const REFERENCE_UA: u32 = 75 - STANDBY_UA;
+            const REFERENCE_CONVERSIONS: u32 = 10 * 512;
+
+            let conversions = self.odr.hz() * u32::from(self.osr.ratio());
+            STANDBY_UA + REFERENCE_UA * conversions / REFERENCE_CONVERSIONS
+        }
+
+        /// Estimate how long a battery of `capacity_mah` would power the device in continuous
+        /// measurement mode, in hours.
+        ///
+        /// This is [`Self::estimated_current_ua`] drawn steadily from the full capacity: it
+        /// only covers the magnetometer's own contribution, ignoring the microcontroller and any
+        /// other loads, and effects such as self-discharge, so is a planning aid rather than a
+        /// prediction.
+        #[cfg(feature = "float")]
+        #[must_use]
+        pub fn estimated_runtime_hours(&self, capacity_mah: f32) -> f32 {
+            // The estimate is at most a few thousand µA, so is exact as an `f32`
+            #[allow(clippy::cast_precision_loss)]
+            let current_ma = self.estimated_current_ua() as f32 / 1000.0;
+            capacity_mah / current_ma
+        }
+
+        /// Estimate the worst-case time between a change in the field and the data reflecting
+        /// it being ready, in µs.
+        ///
+        /// This is one sample period at the configured [`OutputDataRate`] (a change may arrive
+        /// just after a conversion started), plus the conversion time (see
+        /// [`Self::conversion_us`]).
+        /// It's intended for roughly time-aligning readings with other sensors, not as a precise
+        /// figure.
+        #[must_use]
+        pub fn latency_us(&self) -> u32 {
+            let period_us = 1_000_000 / self.odr.hz();
+            period_us + self.conversion_us()
+        }
+
+        /// Estimate the noise floor of readings with these settings, in Gauss: the smallest
+        /// change in field which can be told apart from noise.
+        ///
+        /// This is a nominal estimate from the datasheet's 2mG RMS noise at 512x oversampling,
+        /// which grows with the square root of the reduction in oversampling (ie. by √2 each
+        /// time it's halved), limited below by the resolution of the [`FullScale`] (see
+        /// [`FullScale::resolution_gauss`]).
+        /// It's not a measured value, but is a starting point for thresholds, eg. for detecting
+        /// magnets or anomalies.
+        #[cfg(feature = "float")]
+        #[must_use]
+        pub fn noise_floor_gauss(&self) -> f32 {
+            use core::f32::consts::SQRT_2;
+            const NOISE_OSR512_GAUSS: f32 = 0.002;
+
+            let factor = match self.osr {
+                OverSampleRatio::OSR512 => 1.0,
+                OverSampleRatio::OSR256 => SQRT_2,
+                OverSampleRatio::OSR128 => 2.0,
+                OverSampleRatio::OSR64 => 2.0 * SQRT_2,
+            };
+            (NOISE_OSR512_GAUSS * factor).max(self.rng.resolution_gauss())
+        }
+
+        /// Estimate the time taken to convert one measurement of all three axes, in µs.
+        ///
+        /// The datasheet doesn't give conversion times, so this is a nominal 8µs per oversample,
+        /// with the axes converted one after another.
+        #[must_use]
+        pub fn conversion_us(&self) -> u32 {
+            const US_PER_OVERSAMPLE: u32 = 8;
+            const AXES: u32 = 3;
+
+            AXES * US_PER_OVERSAMPLE * u32::from(self.osr.ratio())
+        }
+
+        /// Suggest an I2C bus frequency, in Hz, which avoids problems with clock stretching.
+        ///
+        /// Some clones stretch the clock while a conversion is in progress, which can make
+        /// transactions time out on a fast bus.
+        /// If conversions take up more than half of each sample period (see
+        /// [`Self::conversion_us`]), a read is likely to coincide with one, so standard mode
+        /// (100kHz) is suggested; otherwise, fast mode (400kHz) is.
+        /// This is advisory: genuine parts handle fast mode with any settings.
+        #[must_use]
+        pub fn recommended_bus_hz(&self) -> u32 {
+            const STANDARD_MODE_HZ: u32 = 100_000;
+            const FAST_MODE_HZ: u32 = 400_000;
+
+            let period_us = 1_000_000 / self.odr.hz();
+            if self.conversion_us() > period_us / 2 {
+                STANDARD_MODE_HZ
+            } else {
+                FAST_MODE_HZ
+            }
+        }
+
+        /// Get the output data rate which can realistically be achieved with these settings.
+        ///
+        /// Heavier oversampling makes each conversion take longer, so a high [`OutputDataRate`]
+        /// combined with a high [`OverSampleRatio`] can't be met.
+        /// This returns the fastest rate, no faster than the configured one, whose sample period
+        /// is at least [`Self::conversion_us`]: with the nominal conversion times, 512x
+        /// oversampling is limited to 50Hz and 256x to 100Hz, while 128x and 64x can reach 200Hz.
+        /// The datasheet doesn't state these limits explicitly, so treat them as a guide.
+        ///
+        /// A warning is logged when the `defmt` feature is enabled and the rate had to be
+        /// lowered.
+        #[must_use]
+        pub fn effective_odr(&self) -> OutputDataRate {
+            let effective = self.feasible_odr();
+            #[cfg(all(feature = "defmt", feature = "log"))]
+            if effective != self.odr {
+                defmt::warn!(
+                    "{} can't be reached with {}, expect {}",
+                    self.odr,
+                    self.osr,
+                    effective
+                );
+            }
+            effective
+        }
+
+        /// Check whether the [`OutputDataRate`] can be achieved with the [`OverSampleRatio`].
+        ///
+        /// With the nominal conversion times (see [`Self::effective_odr`]), the fastest rate
+        /// each oversampling ratio supports is:
+        ///
+        /// | Oversampling | Fastest rate |
+        /// |--------------|--------------|
+        /// | 512x         | 50Hz         |
+        /// | 256x         | 100Hz        |
+        /// | 128x, 64x    | 200Hz        |
+        ///
+        /// [`crate::QMC8553L::change_settings`] rejects settings which fail this check.
+        #[must_use]
+        pub fn is_supported(&self) -> bool {
+            self.feasible_odr() == self.odr
+        }
+
+        /// Get these settings with the [`OutputDataRate`] lowered to the fastest one the
+        /// [`OverSampleRatio`] supports, if necessary (see [`Self::is_supported`]).
+        ///
+        /// The oversampling is kept, since it sets the noise level.
+        #[must_use]
+        pub fn clamped(self) -> Self {
+            Self {
+                odr: self.feasible_odr(),
+                ..self
+            }
+        }
+
+        /// The fastest rate, no faster than the configured one, which the oversampling allows.
+        fn feasible_odr(self) -> OutputDataRate {
+            const FASTEST_FIRST: [OutputDataRate; 4] = [
+                OutputDataRate::OSR200,
+                OutputDataRate::OSR100,
+                OutputDataRate::OSR50,
+                OutputDataRate::OSR10,
+            ];
+
+            let conversion_us = self.conversion_us();
+            FASTEST_FIRST
+                .into_iter()
+                .filter(|odr| odr.hz() <= self.odr.hz())
+                .find(|odr| 1_000_000 / odr.hz() >= conversion_us)
+                .unwrap_or(OutputDataRate::OSR10)
+        }
+    }
+
+    #[cfg(feature = "ufmt")]
+    impl ufmt::uDisplay for Settings {
+        fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+        where
+            W: ufmt::uWrite + ?Sized,
+        {
+            let gauss: u8 = match self.rng {
+                FullScale::RNG2G => 2,
+                FullScale::RNG8G => 8,
+            };
+            ufmt::uwrite!(
+                f,
+                "{}Hz, {}x oversampling, +/-{}G",
+                self.odr.hz(),
+                self.osr.ratio(),
+                gauss
+            )
+        }
     }
 
     impl From<Settings> for u8 {
+        /// Encode the settings as a value of the settings register, in continuous mode.
+        ///
+        /// See [`Settings::to_raw`] to choose the mode.
         fn from(set: Settings) -> Self {
-            let mut val = 0;
-            val += (set.odr as u8) << 2;
-            val += (set.rng as u8) << 4;
-            val += (set.osr as u8) << 6;
-            // We add 1 to reflect the continuous measurement mode
-            val += 1;
-            val
+            set.to_raw(Mode::Continuous)
+        }
+    }
+
+    /// A settings register value which couldn't be decoded into [`Settings`].
+    ///
+    /// Holds the value as read: the range or mode bits contain one of the values reserved by
+    /// the datasheet.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "defmt", derive(Format))]
+    #[cfg_attr(feature = "ufmt", derive(uDebug))]
+    pub struct InvalidSettings(pub u8);
+
+    impl Settings {
+        /// Decode each field from a value of the settings register.
+        ///
+        /// The two-bit rate and oversampling fields have a variant for every value, so always
+        /// decode; the range only has two of its four values defined.
+        fn decode(val: u8) -> (OutputDataRate, Option<FullScale>, OverSampleRatio) {
+            (
+                OutputDataRate::n((val & 0b0000_1100) >> 2).unwrap_or_default(),
+                FullScale::n((val & 0b0011_0000) >> 4),
+                OverSampleRatio::n((val & 0b1100_0000) >> 6).unwrap_or_default(),
+            )
+        }
+
+        /// Decode a value of the settings register, failing if the range is a reserved value.
+        ///
+        /// The mode bits are ignored.
+        /// This is the checked counterpart of the [`From<u8>`] implementation: since that
+        /// exists, `TryFrom<u8>` is already implemented (infallibly) by the standard library.
+        pub fn try_from_bits(val: u8) -> Result<Self, InvalidSettings> {
+            let (odr, rng, osr) = Self::decode(val);
+            Ok(Self {
+                odr,
+                rng: rng.ok_or(InvalidSettings(val))?,
+                osr,
+            })
+        }
+
+        /// Decode every field of a value of the settings register, including the [`Mode`].
+        ///
+        /// Unlike the [`From<u8>`] implementation, this is lossless: encoding the result with
+        /// [`Self::to_raw`] gives back `val`.
+        /// Values with reserved range or mode bits can't be represented, so fail.
+        pub fn from_raw(val: u8) -> Result<(Self, Mode), InvalidSettings> {
+            let set = Self::try_from_bits(val)?;
+            match Mode::from(val) {
+                Mode::Reserved => Err(InvalidSettings(val)),
+                mode => Ok((set, mode)),
+            }
+        }
+
+        /// Encode the settings as a value of the settings register, with the given [`Mode`].
+        ///
+        /// [`Mode::Reserved`] is encoded as `0b10`, one of the reserved values.
+        #[must_use]
+        pub const fn to_raw(self, mode: Mode) -> u8 {
+            let mode = match mode {
+                Mode::Standby => 0b00,
+                Mode::Continuous => 0b01,
+                Mode::Reserved => 0b10,
+            };
+            ((self.osr as u8) << 6) | ((self.rng as u8) << 4) | ((self.odr as u8) << 2) | mode
         }
     }
 
     impl From<u8> for Settings {
+        /// Decode a value of the settings register.
+        ///
+        /// A reserved range value decodes as the default [`FullScale`]: use
+        /// [`Settings::try_from_bits`] to detect this.
+        /// The mode bits are ignored.
         fn from(val: u8) -> Self {
+            let (odr, rng, osr) = Self::decode(val);
             Self {
-                odr: OutputDataRate::n((val & 0b1100_0000) >> 6).unwrap(),
-                rng: FullScale::n((val & 0b0011_0000) >> 4).unwrap(),
-                osr: OverSampleRatio::n((val & 0b0000_1100) >> 2).unwrap(),
+                odr,
+                rng: rng.unwrap_or_default(),
+                osr,
             }
         }
     }
@@ -103,6 +643,13 @@ pub mod settings {
     mod tests {
         use super::*;
 
+        #[test]
+        fn resolution() {
+            // 1/12000 and 1/3000 Gauss per LSB, from the datasheet
+            assert!((FullScale::RNG2G.resolution_gauss() - 8.333e-5).abs() < 1e-8);
+            assert!((FullScale::RNG8G.resolution_gauss() - 3.333e-4).abs() < 1e-7);
+        }
+
         #[test]
         fn sanity() {
             let set = Settings::default();
@@ -111,11 +658,320 @@ pub mod settings {
 
             assert_eq!(<u8 as Into<Settings>>::into(intermediate_val), set);
         }
+
+        #[test]
+        fn decode_every_byte() {
+            for val in 0..=u8::MAX {
+                let set = Settings::from(val);
+                let reserved_range = val & 0b0010_0000 != 0;
+                match Settings::try_from_bits(val) {
+                    Ok(decoded) => {
+                        assert!(!reserved_range);
+                        assert_eq!(decoded, set);
+                        assert_eq!(Settings::try_from_bits(u8::from(set)), Ok(set));
+                    }
+                    Err(err) => {
+                        assert!(reserved_range);
+                        assert_eq!(err, InvalidSettings(val));
+                        assert_eq!(set.rng, FullScale::default());
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn default_raw() {
+            assert_eq!(Settings::DEFAULT_RAW, u8::from(Settings::default()));
+        }
+
+        #[test]
+        #[cfg(feature = "float")]
+        fn noise_floor() {
+            let floor = |osr| {
+                Settings {
+                    osr,
+                    ..Settings::default()
+                }
+                .noise_floor_gauss()
+            };
+            let floors = [
+                floor(OverSampleRatio::OSR64),
+                floor(OverSampleRatio::OSR128),
+                floor(OverSampleRatio::OSR256),
+                floor(OverSampleRatio::OSR512),
+            ];
+            assert!(floors.windows(2).all(|pair| pair[1] < pair[0]));
+            assert!((floors[3] - 0.002).abs() < 1e-6);
+        }
+
+        #[test]
+        fn recommended_bus_speed() {
+            let set = |odr, osr| Settings {
+                odr,
+                osr,
+                ..Settings::default()
+            };
+            assert_eq!(
+                set(OutputDataRate::OSR10, OverSampleRatio::OSR512).recommended_bus_hz(),
+                400_000
+            );
+            for odr in [
+                OutputDataRate::OSR10,
+                OutputDataRate::OSR50,
+                OutputDataRate::OSR100,
+                OutputDataRate::OSR200,
+            ] {
+                let speeds = [
+                    OverSampleRatio::OSR64,
+                    OverSampleRatio::OSR128,
+                    OverSampleRatio::OSR256,
+                    OverSampleRatio::OSR512,
+                ]
+                .map(|osr| set(odr, osr).recommended_bus_hz());
+                assert!(speeds.windows(2).all(|pair| pair[1] <= pair[0]));
+            }
+            assert_eq!(
+                set(OutputDataRate::OSR100, OverSampleRatio::OSR256).recommended_bus_hz(),
+                100_000
+            );
+        }
+
+        #[test]
+        fn typed_accessors_round_trip() {
+            for odr in [
+                OutputDataRate::OSR10,
+                OutputDataRate::OSR50,
+                OutputDataRate::OSR100,
+                OutputDataRate::OSR200,
+            ] {
+                assert_eq!(odr.rate().raw(), odr.hz());
+                assert_eq!(OutputDataRate::from_rate(odr.rate()), Some(odr));
+            }
+            assert_eq!(
+                OutputDataRate::from_rate(fugit::HertzU32::from_raw(20)),
+                None
+            );
+
+            for osr in [
+                OverSampleRatio::OSR512,
+                OverSampleRatio::OSR256,
+                OverSampleRatio::OSR128,
+                OverSampleRatio::OSR64,
+            ] {
+                assert_eq!(osr.oversamples(), Oversamples(osr.ratio()));
+                assert_eq!(
+                    OverSampleRatio::from_oversamples(osr.oversamples()),
+                    Some(osr)
+                );
+            }
+            assert_eq!(OverSampleRatio::from_oversamples(Oversamples(100)), None);
+        }
+
+        #[test]
+        fn supported_combos() {
+            let set = |odr, osr| Settings {
+                odr,
+                osr,
+                ..Settings::default()
+            };
+            assert!(set(OutputDataRate::OSR50, OverSampleRatio::OSR512).is_supported());
+            assert!(!set(OutputDataRate::OSR100, OverSampleRatio::OSR512).is_supported());
+            assert!(set(OutputDataRate::OSR100, OverSampleRatio::OSR256).is_supported());
+            assert!(!set(OutputDataRate::OSR200, OverSampleRatio::OSR256).is_supported());
+            assert!(set(OutputDataRate::OSR200, OverSampleRatio::OSR128).is_supported());
+            assert_eq!(
+                set(OutputDataRate::OSR200, OverSampleRatio::OSR512).clamped(),
+                set(OutputDataRate::OSR50, OverSampleRatio::OSR512)
+            );
+        }
+
+        #[test]
+        fn raw_round_trip() {
+            for val in 0..=u8::MAX {
+                let reserved = val & 0b0010_0000 != 0 || val & Settings::MODE_MASK > 0b01;
+                match Settings::from_raw(val) {
+                    Ok((set, mode)) => {
+                        assert!(!reserved);
+                        assert_eq!(set.to_raw(mode), val);
+                    }
+                    Err(err) => {
+                        assert!(reserved);
+                        assert_eq!(err, InvalidSettings(val));
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn latency() {
+            let low_osr = Settings {
+                osr: OverSampleRatio::OSR64,
+                ..Settings::default()
+            };
+            let high_osr = Settings {
+                osr: OverSampleRatio::OSR512,
+                ..low_osr
+            };
+            let fast = Settings {
+                odr: OutputDataRate::OSR200,
+                ..low_osr
+            };
+
+            assert!(high_osr.latency_us() > low_osr.latency_us());
+            assert!(fast.latency_us() < low_osr.latency_us());
+        }
+
+        #[test]
+        fn effective_odr_limited_by_osr() {
+            let cases = [
+                (
+                    OutputDataRate::OSR200,
+                    OverSampleRatio::OSR512,
+                    OutputDataRate::OSR50,
+                ),
+                (
+                    OutputDataRate::OSR100,
+                    OverSampleRatio::OSR512,
+                    OutputDataRate::OSR50,
+                ),
+                (
+                    OutputDataRate::OSR200,
+                    OverSampleRatio::OSR256,
+                    OutputDataRate::OSR100,
+                ),
+                (
+                    OutputDataRate::OSR200,
+                    OverSampleRatio::OSR128,
+                    OutputDataRate::OSR200,
+                ),
+                (
+                    OutputDataRate::OSR10,
+                    OverSampleRatio::OSR512,
+                    OutputDataRate::OSR10,
+                ),
+                (
+                    OutputDataRate::OSR50,
+                    OverSampleRatio::OSR64,
+                    OutputDataRate::OSR50,
+                ),
+            ];
+            for (odr, osr, expected) in cases {
+                let set = Settings {
+                    odr,
+                    osr,
+                    ..Settings::default()
+                };
+                assert_eq!(set.effective_odr(), expected);
+            }
+        }
+
+        #[test]
+        fn current_increases_with_rate() {
+            let slow = Settings {
+                odr: OutputDataRate::OSR10,
+                osr: OverSampleRatio::OSR64,
+                ..Settings::default()
+            };
+            let faster_rate = Settings {
+                odr: OutputDataRate::OSR200,
+                ..slow
+            };
+            let more_samples = Settings {
+                osr: OverSampleRatio::OSR512,
+                ..slow
+            };
+
+            assert!(faster_rate.estimated_current_ua() > slow.estimated_current_ua());
+            assert!(more_samples.estimated_current_ua() > slow.estimated_current_ua());
+            assert_eq!(
+                Settings {
+                    odr: OutputDataRate::OSR10,
+                    osr: OverSampleRatio::OSR512,
+                    ..slow
+                }
+                .estimated_current_ua(),
+                75
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "float")]
+        fn runtime_from_capacity() {
+            let set = Settings {
+                odr: OutputDataRate::OSR10,
+                osr: OverSampleRatio::OSR512,
+                ..Settings::default()
+            };
+            // 75µA from a 225mAh coin cell
+            assert!((set.estimated_runtime_hours(225.0) - 3000.0).abs() < 0.1);
+            let faster = Settings {
+                odr: OutputDataRate::OSR50,
+                ..set
+            };
+            assert!(faster.estimated_runtime_hours(225.0) < 3000.0);
+        }
     }
 }
 
+/// Errors which can occur when using the driver.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// An error on the I2C bus.
+    I2c(E),
+    /// The readings overflowed the configured range.
+    Overflow,
+    /// A value read back from the device didn't match what was written to it.
+    Verify,
+    /// The device didn't respond as expected within the allowed time.
+    Timeout,
+    /// The field had zero magnitude, so has no direction.
+    ZeroField,
+    /// The interrupt pin couldn't be read.
+    Pin,
+    /// The register isn't documented as writable.
+    ///
+    /// See [`RegisterAddr::is_writable`].
+    Forbidden,
+    /// Consecutive readings differed by more than physically plausible, even after retrying.
+    ///
+    /// See [`QMC8553L::set_max_delta`].
+    Glitch,
+    /// No new data was produced within the allowed time.
+    ///
+    /// See [`QMC8553L::watchdog`].
+    Stalled,
+    /// The chip ID didn't match [`DeviceInfo::EXPECTED_CHIP_ID`].
+    ///
+    /// See [`QMC8553L::bring_up`].
+    UnexpectedChipId,
+    /// The settings didn't take effect during initialisation, even after retrying.
+    ///
+    /// See [`QMC8553L::new`].
+    Config,
+    /// The [`settings::OutputDataRate`] can't be achieved with the
+    /// [`settings::OverSampleRatio`].
+    ///
+    /// See [`Settings::is_supported`].
+    UnsupportedCombo,
+    /// A reading came close to the limit of the configured range, without overflowing.
+    ///
+    /// See [`QMC8553L::set_overflow_margin_permille`].
+    NearOverflow,
+    /// No [`settings::OverSampleRatio`] gives the requested precision.
+    ///
+    /// See [`QMC8553L::read_all_at_precision`].
+    Unattainable,
+    /// The device is on standby, and strict mode is enabled.
+    ///
+    /// See [`QMC8553L::set_strict`].
+    InStandby,
+}
+
 /// An axis of the sensor.
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
 #[allow(missing_docs)]
 pub enum Axis {
     X,
@@ -123,6 +979,191 @@ pub enum Axis {
     Z,
 }
 
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for Axis {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.write_str(match self {
+            Axis::X => "X",
+            Axis::Y => "Y",
+            Axis::Z => "Z",
+        })
+    }
+}
+
+/// How [`QMC8553L::read_all`] handles readings outside the rated range of the configured
+/// [`settings::FullScale`] (see [`settings::FullScale::max_counts`]).
+///
+/// Set with [`QMC8553L::set_overflow_policy`].
+/// Checking the range needs no extra bus access.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum OverflowPolicy {
+    /// Return readings as the device reports them, out of range or not.
+    #[default]
+    Raw,
+    /// Return [`Error::Overflow`] if any axis is out of range.
+    Error,
+    /// Pin out-of-range axes to the bounds of the range.
+    Clamp,
+}
+
+/// The units to convert readings to with [`QMC8553L::read_all_in`].
+///
+/// Every conversion starts from the raw counts, which are divided by the sensitivity of the
+/// configured [`settings::FullScale`] to get Gauss (see [`settings::FullScale::scale_factor`]),
+/// then scaled from Gauss as needed.
+#[cfg(feature = "float")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Units {
+    /// The counts (LSB) reported by the device, unconverted.
+    Raw,
+    /// Gauss: counts divided by the sensitivity.
+    Gauss,
+    /// Microtesla: 100µT per Gauss.
+    MicroTesla,
+    /// Milligauss: 1000mG per Gauss.
+    Milligauss,
+}
+
+#[cfg(feature = "float")]
+impl Units {
+    /// The value of one count in these units, in the given range.
+    ///
+    /// This uses the datasheet sensitivity: see [`QMC8553L::set_sensitivity`].
+    #[must_use]
+    pub fn per_count(self, rng: settings::FullScale) -> f32 {
+        self.per_count_with(rng.scale_factor())
+    }
+
+    /// The value of one count in these units, with the given sensitivity in counts per Gauss.
+    fn per_count_with(self, counts_per_gauss: f32) -> f32 {
+        match self {
+            Self::Raw => 1.0,
+            Self::Gauss => 1.0 / counts_per_gauss,
+            Self::MicroTesla => 100.0 / counts_per_gauss,
+            Self::Milligauss => 1000.0 / counts_per_gauss,
+        }
+    }
+}
+
+/// How [`QMC8553L::is_ready_clocked`] decides whether new data is ready.
+///
+/// Set with [`QMC8553L::set_ready_strategy`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum ReadyStrategy {
+    /// Check the data ready bit of the [`Status`] register, as [`QMC8553L::is_ready`] does.
+    #[default]
+    StatusBit,
+    /// Assume new data is ready once a sample period at the configured
+    /// [`settings::OutputDataRate`] has passed since data was last reported ready.
+    ///
+    /// This is for clones which don't set the data ready bit correctly, and needs no bus
+    /// access.
+    /// The [`Clock`] passed to [`QMC8553L::is_ready_clocked`] must tick `ticks_per_second` times
+    /// per second.
+    /// If the clock drifts relative to the device, data may be read before it's updated (giving
+    /// a stale repeat) or samples may be skipped.
+    FixedRate {
+        /// The rate of the [`Clock`].
+        ticks_per_second: u32,
+    },
+}
+
+/// Changes in the [`Status`] register, as reported by [`QMC8553L::poll_events`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+// These are independent flags, so wouldn't be clearer as enums
+#[allow(clippy::struct_excessive_bools)]
+pub struct Events {
+    /// New data is ready to be read ([`Status::DRDY`] is set).
+    pub data_ready: bool,
+    /// An axis is out of range ([`Status::OVL`] is set).
+    ///
+    /// This is a sustained condition: it stays set for as long as the field is out of range.
+    pub overflow: bool,
+    /// An axis has just gone out of range ([`Status::OVL`] was clear, and is now set).
+    pub overflow_started: bool,
+    /// Every axis has just returned to range ([`Status::OVL`] was set, and is now clear).
+    pub overflow_ended: bool,
+    /// Data was skipped because it wasn't read in time ([`Status::DOR`] is set).
+    pub data_skipped: bool,
+}
+
+/// Identifying information read from the device by [`QMC8553L::device_info`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct DeviceInfo {
+    /// The contents of the chip ID register ([`RegisterAddr::ChipId`]).
+    pub chip_id: u8,
+}
+
+impl DeviceInfo {
+    /// The chip ID given by the datasheet for the QMC5883L.
+    pub const EXPECTED_CHIP_ID: u8 = 0xff;
+
+    /// Check whether the chip ID matches the datasheet.
+    ///
+    /// Some clones and relabelled parts report a different ID.
+    #[must_use]
+    pub fn is_expected(&self) -> bool {
+        self.chip_id == Self::EXPECTED_CHIP_ID
+    }
+}
+
+/// The decoded contents of every register on the device, read by [`QMC8553L::snapshot`].
+///
+/// This is intended for diagnostics, eg. to attach to a bug report.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct Snapshot {
+    /// The axis data registers (`0x00` to `0x05`), as reported by the device.
+    ///
+    /// The configured byte order is applied, but not the remapping, frame or overflow policy.
+    pub data: Measurement,
+    /// The status register (`0x06`).
+    pub status: Status,
+    /// The raw temperature registers (`0x07` and `0x08`), as in [`QMC8553L::get_temp`].
+    pub temperature: i16,
+    /// The settings held in the first control register (`0x09`), or [`None`] if it has
+    /// reserved bits set.
+    pub settings: Option<Settings>,
+    /// The mode held in the first control register (`0x09`).
+    pub mode: settings::Mode,
+    /// The second control register (`0x0A`).
+    pub control2: Control2,
+    /// The SET/RESET period register (`0x0B`).
+    pub set_reset_period: u8,
+    /// The chip ID register (`0x0D`).
+    pub chip_id: u8,
+}
+
+/// Statistics from a run of [`QMC8553L::sample_loop`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct SampleStats {
+    /// The number of samples read.
+    pub read: usize,
+    /// The number of samples after which the device reported that data had been skipped.
+    ///
+    /// The device doesn't report *how many* samples were dropped, so this is a lower bound on the
+    /// total dropped.
+    /// If this is non-zero, the samples aren't being read quickly enough for the configured
+    /// [`settings::OutputDataRate`].
+    pub skipped: usize,
+}
+
 impl From<Axis> for registers::Register16 {
     fn from(value: Axis) -> Self {
         match value {
@@ -153,105 +1194,5359 @@ impl From<Axis> for registers::Register16 {
 /// ```
 pub struct QMC8553L<I: I2c> {
     i2c: I,
-    standby: bool,
-    // TODO: cache settings here?
-    // we should be able to (in order to save bus throughput) since we always explicitly set them
-    // on initialisation, and can just cache them then
+    addr: u8,
+    // The single source of truth for the power state: kept in step with every write which can
+    // change it
+    mode: settings::Mode,
+    // We always explicitly set these on initialisation, so we can cache them to save bus
+    // throughput
+    settings: Settings,
+    // Set by `invalidate_cache`, so the next read of the settings refreshes the cache
+    cache_stale: bool,
+    strict: bool,
+    hard_iron: HardIron,
+    declination: f32,
+    scale: (f32, f32, f32),
+    remap: AxisRemap,
+    frame: Frame,
+    poll_interval: Option<u32>,
+    explicit_reads: bool,
+    overflow_policy: OverflowPolicy,
+    ready_strategy: ReadyStrategy,
+    last_ready_tick: Option<u64>,
+    last_status: Status,
+    auto_calibrator: Option<Calibrator>,
+    zero: HardIron,
+    max_delta: Option<i16>,
+    last_reading: Option<Measurement>,
+    last_changed: Option<Measurement>,
+    saturation_counts: [u32; 3],
+    overflow_margin: Option<u16>,
+    samples_read: u32,
+    cached_temp: Option<i16>,
+    temp_age: u16,
+    byte_order: ByteOrder,
+    #[cfg(feature = "float")]
+    last_cardinal: Option<Cardinal>,
+    #[cfg(feature = "float")]
+    cardinal_margin: f32,
+    #[cfg(feature = "float")]
+    temp_coefficient: f32,
+    #[cfg(feature = "float")]
+    reference_temp: i16,
+    #[cfg(feature = "float")]
+    sensitivity: [f32; 2],
 }
 
 impl<I: I2c> QMC8553L<I> {
+    /// The standard address of the device on the I2C bus.
+    pub const DEFAULT_ADDR: u8 = 0x0D;
+
+    /// The largest per-axis difference, in counts, allowed between readings in
+    /// [`Self::self_test`].
+    pub const SELF_TEST_TOLERANCE: i16 = 200;
+
+    /// The longest time to wait for a soft reset to complete in [`Self::reset_with_delay`], in
+    /// microseconds.
+    pub const RESET_TIMEOUT_US: u32 = 10_000;
+
+    /// The decay applied before every reading in auto-calibration mode, as a shift for
+    /// [`Calibrator::decay`]: each reading, the extremes shrink by `1/256` of their span.
+    pub const AUTO_CALIBRATE_DECAY_SHIFT: u32 = 8;
+
+    /// The [`Calibrator::quality`] needed before auto-calibration updates the hard-iron offsets.
+    pub const AUTO_CALIBRATE_MIN_QUALITY: f32 = 0.5;
+
+    /// The number of times a reading is retried after a glitch, before returning
+    /// [`Error::Glitch`].
+    ///
+    /// See [`Self::set_max_delta`].
+    pub const GLITCH_RETRIES: u8 = 3;
+
+    /// The number of times initialisation re-applies the [`Settings`] if they don't read back
+    /// correctly, before giving up with [`Error::Config`].
+    pub const INIT_RETRIES: u8 = 3;
+
+    /// The number of samples taken by [`Self::is_settled`].
+    pub const SETTLE_SAMPLES: u8 = 16;
+
+    /// The largest [`Self::stability`] value, in counts squared, for which [`Self::is_settled`]
+    /// considers the field stable.
+    pub const SETTLE_MAX_VARIANCE: u32 = 5_000;
+
+    /// The largest change in the raw temperature, in counts (about 100 per °C), across the
+    /// samples of [`Self::is_settled`] for which it considers the temperature stable.
+    pub const SETTLE_MAX_TEMP_DRIFT: i16 = 50;
+
+    /// The default hysteresis margin used by [`Self::cardinal`], in degrees.
+    #[cfg(feature = "float")]
+    pub const DEFAULT_CARDINAL_MARGIN: f32 = 5.0;
+
+    /// The nominal gain of the temperature sensor, in counts per °C.
+    #[cfg(feature = "float")]
+    const TEMP_COUNTS_PER_C: f32 = 100.0;
+
     /// Initialise the device with the given [`Settings`].
     ///
     /// # Notes
     ///
     /// - As part of this process, perform a sofware reset of the device
     /// - The device will **not** be in "Standby" mode afterwards
-    pub fn new(i2c: I, set: Settings) -> Result<Self, I::Error> {
-        let mut to_ret = Self {
-            i2c,
-            standby: false,
-        };
-        to_ret.reset()?;
-        to_ret.change_settings(set)?;
-        Ok(to_ret)
-    }
-
-    /// Perform a soft reset of the device.
-    ///
-    /// This **does not** place the device into "Standby" mode!
-    pub fn reset(&mut self) -> Result<(), I::Error> {
-        use registers::Control2;
-        #[cfg(feature = "defmt")]
-        debug!("Resetting QMC8553L magnetometer");
-        self.set_control2(Control2::SOFT_RST)?;
-        // TODO: delay period?
-        // Reenable pointer rollover
-        #[cfg(feature = "defmt")]
-        debug!("Enabling pointer rollover");
-        self.set_control2(Control2::ROL_PNT)?;
-        // TODO: if we write interrupts code in the future, we need to enable them here!
-        Ok(())
+    /// - Pointer rollover is checked after the reset: if the device doesn't honour it (as with
+    ///   some clones), the driver falls back to explicit reads (see [`Self::set_explicit_reads`])
+    ///   so that readings are still correct, and logs a warning
+    /// - The settings are read back after being written: on a fast MCU, the write can race the
+    ///   device's reset and be lost, so after waiting for the reset to complete it's re-applied
+    ///   up to [`Self::INIT_RETRIES`] times before returning [`Error::Config`]
+    pub fn new(i2c: I, set: Settings) -> Result<Self, Error<I::Error>> {
+        Self::new_with_address(i2c, set, Self::DEFAULT_ADDR)
     }
 
-    /// Set the "Standby" mode on the device to conserve power.
+    /// Initialise a device at a non-standard address with the given [`Settings`].
     ///
-    /// All interaction with the device afterwards will automatically wake it up.
-    pub fn to_standby(&mut self) -> Result<(), I::Error> {
-        let mut set_val: u8 = self.settings()?.into();
-        // unset the continuous measurement bit
-        set_val &= 0b1111_1100;
-        #[cfg(feature = "defmt")]
-        debug!("Sending QMC5883L to standby mode");
-        self.write_raw(Settings::ADDR, set_val)?;
-        self.standby = true;
-        Ok(())
+    /// This is only needed for some clones and relabelled parts: prefer [`Self::new`].
+    pub fn new_with_address(i2c: I, set: Settings, addr: u8) -> Result<Self, Error<I::Error>> {
+        Self::init(i2c, set, addr, settings::Mode::Continuous)
     }
 
-    /// Check if the device is on standby.
+    /// Initialise the device with the given [`Settings`], leaving it on standby.
     ///
-    /// The user should note that this is only tracked in software (otherwise checking the flag
-    /// would wake the device up!).
-    pub fn on_standby(&self) -> bool {
-        self.standby
+    /// This is as [`Self::new`], except that the device never starts measuring: the settings are
+    /// written with the standby mode bits, so there's no initial burst of continuous
+    /// measurement.
+    /// This suits battery-powered devices which only wake the sensor on demand (eg. with
+    /// [`Self::read_once_low_power`]): the device draws only its standby current (around 3µA)
+    /// until then.
+    pub fn new_standby(i2c: I, set: Settings) -> Result<Self, Error<I::Error>> {
+        Self::init(i2c, set, Self::DEFAULT_ADDR, settings::Mode::Standby)
     }
 
-    /// Check if the device is ready to have data read off it.
-    pub fn is_ready(&mut self) -> Result<bool, I::Error> {
-        Ok(self.get_status()?.contains(registers::Status::DRDY))
+    /// Reset the device, then apply the settings in the given mode.
+    fn init(
+        i2c: I,
+        set: Settings,
+        addr: u8,
+        mode: settings::Mode,
+    ) -> Result<Self, Error<I::Error>> {
+        if !set.is_supported() {
+            return Err(Error::UnsupportedCombo);
+        }
+        let mut to_ret = Self::unconfigured(i2c, set, addr);
+        // Mark the SET/RESET period, so its return to the default shows the reset has completed
+        to_ret.write_set_reset_period(0x01).map_err(Error::I2c)?;
+        to_ret.reset().map_err(Error::I2c)?;
+        to_ret.check_rollover().map_err(Error::I2c)?;
+        to_ret.apply_initial_settings(set, mode)?;
+        Ok(to_ret)
     }
 
-    /// Read all three axes' data off the device.
+    /// Write the settings, reading them back and retrying until they've taken effect.
     ///
-    /// You should check with [`Self::is_ready`] before you call this.
-    pub fn read_all(&mut self) -> Result<(i16, i16, i16), I::Error> {
-        self.read_data()
+    /// Before each retry, wait for the reset to complete by polling the SET/RESET period register
+    /// (marked by [`Self::init`] before the reset) until it reads back as its reset default, as
+    /// [`Self::reset_with_delay`] does.
+    /// There's no delay available here, so the bus transactions pace the polling.
+    /// A reset which swallowed the settings will have swallowed the pointer rollover write too,
+    /// so that's re-applied and checked again before the settings.
+    fn apply_initial_settings(
+        &mut self,
+        set: Settings,
+        mode: settings::Mode,
+    ) -> Result<(), Error<I::Error>> {
+        // Around 10ms at 100kHz, in line with `RESET_TIMEOUT_US`
+        const MAX_POLLS: u8 = 100;
+
+        let written = set.to_raw(mode);
+        for attempt in 0..=Self::INIT_RETRIES {
+            if attempt > 0 {
+                // Bus errors are ignored, since the device may not respond mid-reset
+                let mut polls = 0;
+                while polls < MAX_POLLS && !matches!(self.read_set_reset_period(), Ok(0x00)) {
+                    polls += 1;
+                }
+                self.set_control2(Control2::ROL_PNT).map_err(Error::I2c)?;
+                self.explicit_reads = false;
+                self.check_rollover().map_err(Error::I2c)?;
+            }
+            #[cfg(all(feature = "defmt", feature = "log"))]
+            debug!("Applying {:?} to magnetometer in mode {}", set, mode);
+            self.write_raw(Settings::ADDR, written)
+                .map_err(Error::I2c)?;
+            self.settings = set;
+            self.mode = mode;
+            let read = self.control1_raw().map_err(Error::I2c)?;
+            // The mode is managed separately, so only compare the settings
+            if (read ^ written) & !Settings::MODE_MASK == 0 {
+                return Ok(());
+            }
+            #[cfg(all(feature = "defmt", feature = "log"))]
+            warn!(
+                "Wrote settings {=u8:#x}, but read back {=u8:#x}: retrying",
+                written, read
+            );
+        }
+        Err(Error::Config)
     }
 
-    /// Read a particular axis' data.
+    /// Initialise the device with the given [`Settings`], without performing a soft reset.
     ///
-    /// You should check with [`Self::is_ready`] before you call this.
-    pub fn read(&mut self, axis: Axis) -> Result<i16, I::Error> {
-        self.read_reg16(axis.into())
+    /// This is useful on a warm boot, or where the device is shared with other bus users, so
+    /// a reset would disturb configuration made elsewhere.
+    /// Only the settings register is written: anything else (eg. a disabled pointer rollover,
+    /// which the burst reads rely on, or the interrupt pin state) is left as it was, so only use
+    /// this when the device is known to be in a good state.
+    /// Otherwise, prefer [`Self::new`].
+    pub fn new_no_reset(i2c: I, set: Settings) -> Result<Self, Error<I::Error>> {
+        let mut to_ret = Self::unconfigured(i2c, set, Self::DEFAULT_ADDR);
+        to_ret.change_settings(set)?;
+        Ok(to_ret)
     }
 
-    /// Get the temperature of the device, in °C.
+    /// Create the driver for a device which may already be configured, without writing to it.
     ///
-    /// Note that the temperature is *not* expected to be absolutely accurate, but *is* expected to be
-    /// consistent with itself.
-    pub fn get_temp(&mut self) -> Result<i16, I::Error> {
-        self.read_reg16(registers::Register16::TOUT)
+    /// The cached [`Settings`] and mode are read from the device as in [`Self::sync`].
+    /// This suits a warm boot, where the microcontroller has reset but the device hasn't: check
+    /// with [`Self::detect_warm_boot`] whether the device still needs configuring.
+    ///
+    /// ```no_run
+    /// # fn main() {
+    /// # let mock_i2c = embedded_hal_mock::i2c::Mock::new(&[]);
+    /// use qmc5883l::{settings::Settings, QMC8553L};
+    /// let mut mag = QMC8553L::attach(mock_i2c).unwrap();
+    /// if !mag.detect_warm_boot().unwrap() {
+    ///     mag.reset().unwrap();
+    ///     mag.change_settings(Settings::default()).unwrap();
+    /// }
+    /// # }
+    /// ```
+    pub fn attach(i2c: I) -> Result<Self, I::Error> {
+        let mut to_ret = Self::unconfigured(i2c, Settings::default(), Self::DEFAULT_ADDR);
+        to_ret.sync()?;
+        Ok(to_ret)
     }
 
-    /// Get the currently set [`Settings`] on the device.
+    /// Create the driver struct without touching the device.
+    fn unconfigured(i2c: I, set: Settings, addr: u8) -> Self {
+        Self {
+            i2c,
+            addr,
+            mode: settings::Mode::Continuous,
+            settings: set,
+            cache_stale: false,
+            strict: false,
+            hard_iron: HardIron::default(),
+            declination: 0.0,
+            scale: (1.0, 1.0, 1.0),
+            remap: AxisRemap::IDENTITY,
+            frame: Frame::Raw,
+            poll_interval: None,
+            explicit_reads: false,
+            overflow_policy: OverflowPolicy::Raw,
+            ready_strategy: ReadyStrategy::StatusBit,
+            last_ready_tick: None,
+            last_status: Status::empty(),
+            auto_calibrator: None,
+            zero: HardIron::default(),
+            max_delta: None,
+            last_reading: None,
+            last_changed: None,
+            saturation_counts: [0; 3],
+            overflow_margin: None,
+            samples_read: 0,
+            cached_temp: None,
+            temp_age: 0,
+            byte_order: ByteOrder::LittleEndian,
+            #[cfg(feature = "float")]
+            last_cardinal: None,
+            #[cfg(feature = "float")]
+            cardinal_margin: Self::DEFAULT_CARDINAL_MARGIN,
+            #[cfg(feature = "float")]
+            temp_coefficient: 0.0,
+            #[cfg(feature = "float")]
+            reference_temp: 0,
+            #[cfg(feature = "float")]
+            sensitivity: [
+                settings::FullScale::RNG2G.scale_factor(),
+                settings::FullScale::RNG8G.scale_factor(),
+            ],
+        }
+    }
+
+    /// Check that pointer rollover is enabled on the device, falling back to explicit reads if
+    /// not.
+    fn check_rollover(&mut self) -> Result<(), I::Error> {
+        if !self.get_control2()?.contains(Control2::ROL_PNT) {
+            #[cfg(all(feature = "defmt", feature = "log"))]
+            warn!("Pointer rollover couldn't be enabled, falling back to explicit reads");
+            self.explicit_reads = true;
+        }
+        Ok(())
+    }
+
+    /// Check that the device's pointer rollover works, which the driver's burst reads rely on.
+    ///
+    /// The six data registers and the status register are read starting from `0x00`, then the
+    /// data is read again without giving a register address: with rollover working, the
+    /// pointer wraps from the status register back to the X register, so both reads return the
+    /// same data.
+    /// Some clones don't honour [`Control2::ROL_PNT`], in which case the second read continues
+    /// on to the temperature registers.
+    ///
+    /// A `false` result means the burst reads are unreliable on this part: use
+    /// [`Self::set_explicit_reads`].
+    /// A new sample arriving between the two reads also gives `false`, so put the device on
+    /// standby first (see [`Self::to_standby`]) for a conclusive result.
+    pub fn verify_rollover(&mut self) -> Result<bool, I::Error> {
+        let mut first = [0; 7];
+        let mut second = [0; 6];
+        self.bus_read(registers::Register16::X as u8, &mut first)?;
+        self.bus_read_continue(&mut second)?;
+        // The status register read along with the data isn't compared
+        let first = &first[..6];
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        if first != second {
+            warn!(
+                "Pointer rollover failed: read {=[u8]:#04x} then {=[u8]:#04x}",
+                first, second
+            );
+        }
+        Ok(first == second)
+    }
+
+    /// Start configuring a device with a [`Builder`].
+    ///
+    /// This is useful when setting calibration data alongside the [`Settings`]; for the simple
+    /// case, use [`Self::new`].
+    pub fn builder(i2c: I) -> Builder<I> {
+        Builder::new(i2c)
+    }
+
+    /// Perform a soft reset of the device.
+    ///
+    /// This returns every register to its default, which leaves the device on standby: apply
+    /// some [`Settings`] or call [`Self::wake`] to resume measurement.
+    /// See [`Self::reset_with_delay`] to wait for the reset to complete.
+    /// Pointer rollover is re-enabled afterwards, and the interrupt pin is left enabled (its
+    /// reset default), so call [`Self::disable_interrupt`] again if it had been disabled.
+    ///
+    /// Only the device is reset: the driver's software calibration (hard-iron offsets, axis
+    /// scales, declination and so on) is kept.
+    /// There are three flavours of reset:
+    ///
+    /// - This one, which resets the device only.
+    /// - [`Self::reset_to_defaults`], which resets the device then restores the driver's cached
+    ///   configuration to it, keeping the calibration.
+    /// - [`Self::factory_reset`], which resets the device to the default [`Settings`] and clears
+    ///   the calibration too.
+    pub fn reset(&mut self) -> Result<(), I::Error> {
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Resetting QMC8553L magnetometer");
+        self.set_control2(Control2::SOFT_RST)?;
+        // Reenable pointer rollover
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Enabling pointer rollover");
+        self.set_control2(Control2::ROL_PNT)?;
+        Ok(())
+    }
+
+    /// Perform a soft reset of the device, waiting until the reset has actually completed.
+    ///
+    /// To detect completion, the SET/RESET period register is first set to a non-default value,
+    /// then polled after the reset until it reads back as its reset default (`0x00`).
+    /// Bus errors while polling are ignored, since the device may not respond mid-reset.
+    /// Returns [`Error::Timeout`] if the reset hasn't completed after
+    /// [`Self::RESET_TIMEOUT_US`].
+    ///
+    /// As with [`Self::reset`], pointer rollover is re-enabled afterwards, and the device is left
+    /// on standby.
+    pub fn reset_with_delay<D: DelayUs>(&mut self, delay: &mut D) -> Result<(), Error<I::Error>> {
+        const POLL_US: u32 = 50;
+
+        self.write_set_reset_period(0x01).map_err(Error::I2c)?;
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Resetting QMC8553L magnetometer");
+        self.set_control2(Control2::SOFT_RST).map_err(Error::I2c)?;
+
+        let mut waited = 0;
+        while !matches!(self.read_set_reset_period(), Ok(0x00)) {
+            if waited >= Self::RESET_TIMEOUT_US {
+                #[cfg(all(feature = "defmt", feature = "log"))]
+                warn!("Soft reset didn't complete in {}us", waited);
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_US);
+            waited += POLL_US;
+        }
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Soft reset completed after {}us", waited);
+
+        self.set_control2(Control2::ROL_PNT).map_err(Error::I2c)
+    }
+
+    /// Run the full initialisation sequence recommended by the datasheet.
+    ///
+    /// In order, this:
+    ///
+    /// 1. Reads the chip ID, returning [`Error::UnexpectedChipId`] if it doesn't match
+    ///    [`DeviceInfo::EXPECTED_CHIP_ID`] (so nothing is written to an unknown device)
+    /// 2. Performs a soft reset, waiting for it to complete (see [`Self::reset_with_delay`]),
+    ///    which also re-enables pointer rollover
+    /// 3. Checks pointer rollover took effect, falling back to explicit reads if not (as in
+    ///    [`Self::new`])
+    /// 4. Sets the SET/RESET period to the recommended `0x01`
+    /// 5. Re-applies the cached [`Settings`], taking the device off standby
+    ///
+    /// [`Self::new`] can't wait for the reset to complete, so use this after construction where
+    /// a delay is available, eg. after a brown-out.
+    /// Some clones report a different chip ID: use [`Self::reset_to_defaults`] for those.
+    pub fn bring_up<D: DelayUs>(&mut self, delay: &mut D) -> Result<(), Error<I::Error>> {
+        if !self.device_info().map_err(Error::I2c)?.is_expected() {
+            return Err(Error::UnexpectedChipId);
+        }
+        self.reset_with_delay(delay)?;
+        self.check_rollover().map_err(Error::I2c)?;
+        self.write_set_reset_period(0x01).map_err(Error::I2c)?;
+        self.write_settings(self.settings).map_err(Error::I2c)
+    }
+
+    /// Perform a soft reset of the device, then restore the user's configuration.
+    ///
+    /// A bare [`Self::reset`] returns the configuration registers to the silicon's defaults,
+    /// leaving the device out of sync with the cached [`Settings`].
+    /// This resets the device, then re-applies the cached [`Settings`] (taking the device off
+    /// standby) and the SET/RESET period that was configured before the reset.
+    /// Other [`Control2`] flags are left at their defaults, as in [`Self::reset`].
+    pub fn reset_to_defaults(&mut self) -> Result<(), I::Error> {
+        let period = self.read_set_reset_period()?;
+        self.reset()?;
+        self.write_set_reset_period(period)?;
+        self.write_settings(self.settings)
+    }
+
+    /// Reset both the device and the driver's calibration, to start afresh.
+    ///
+    /// The device is reset as in [`Self::reset`], then configured with the recommended
+    /// SET/RESET period and the default [`Settings`], taking it off standby.
+    /// The hard-iron offsets, axis scales, declination, baseline (see [`Self::zero`]) and
+    /// temperature compensation are returned to their defaults, and auto-calibration is
+    /// disabled.
+    /// The axis remapping and other options describing how the device is mounted and read are
+    /// kept.
+    pub fn factory_reset(&mut self) -> Result<(), I::Error> {
+        self.reset()?;
+        self.write_set_reset_period(0x01)?;
+        self.write_settings(Settings::default())?;
+        self.hard_iron = HardIron::default();
+        self.scale = (1.0, 1.0, 1.0);
+        self.declination = 0.0;
+        self.zero = HardIron::default();
+        self.auto_calibrator = None;
+        #[cfg(feature = "float")]
+        {
+            self.temp_coefficient = 0.0;
+            self.reference_temp = 0;
+        }
+        Ok(())
+    }
+
+    /// Read a single register of the device.
+    ///
+    /// This is a low-level method intended for debugging: prefer the typed methods on this
+    /// struct where possible.
+    pub fn read_register(&mut self, reg: RegisterAddr) -> Result<u8, I::Error> {
+        self.read_register_raw(reg.into())
+    }
+
+    /// Write a single register of the device.
+    ///
+    /// This is a low-level method intended for debugging: prefer the typed methods on this
+    /// struct where possible.
+    /// The driver's cached state is **not** updated, so eg. writing [`RegisterAddr::Control1`]
+    /// will leave [`Self::settings`] out of sync with the device.
+    /// The one exception is the measurement mode held in [`RegisterAddr::Control1`], which is
+    /// tracked so that [`Self::mode`] stays correct.
+    ///
+    /// Only the registers documented as writable can be written: others return
+    /// [`Error::Forbidden`] without any bus access (see [`RegisterAddr::is_writable`]).
+    pub fn write_register(&mut self, reg: RegisterAddr, val: u8) -> Result<(), Error<I::Error>> {
+        if !reg.is_writable() {
+            return Err(Error::Forbidden);
+        }
+        self.write_register_unchecked(reg.into(), val)
+            .map_err(Error::I2c)
+    }
+
+    /// Read a register by its raw address, eg. for registers which aren't documented.
+    ///
+    /// Prefer [`Self::read_register`] where possible.
+    pub fn read_register_raw(&mut self, regaddr: u8) -> Result<u8, I::Error> {
+        self.read_raw(regaddr)
+    }
+
+    /// Write a register by its raw address.
+    ///
+    /// Prefer [`Self::write_register`] where possible, and see the notes there.
+    /// As there, addresses which aren't documented as writable return [`Error::Forbidden`]: see
+    /// [`Self::write_register_unchecked`] to write them anyway.
+    pub fn write_register_raw(&mut self, regaddr: u8, val: u8) -> Result<(), Error<I::Error>> {
+        if !registers::is_writable(regaddr) {
+            return Err(Error::Forbidden);
+        }
+        self.write_register_unchecked(regaddr, val)
+            .map_err(Error::I2c)
+    }
+
+    /// Write a register by its raw address, without checking it's writable.
+    ///
+    /// This is an escape hatch for experimenting with undocumented registers: writing reserved
+    /// registers may leave the device misconfigured until it's power cycled, so prefer
+    /// [`Self::write_register`].
+    pub fn write_register_unchecked(&mut self, regaddr: u8, val: u8) -> Result<(), I::Error> {
+        self.write_raw(regaddr, val)?;
+        if regaddr == Settings::ADDR {
+            self.mode = settings::Mode::from(val);
+        }
+        Ok(())
+    }
+
+    /// Return the device to a known state after a failed bus transaction.
+    ///
+    /// If a transaction fails part-way through, the device's register pointer may be left
+    /// anywhere, and the burst reads used by this driver (which rely on pointer rollover) may then
+    /// return misaligned data.
+    /// This re-enables pointer rollover and moves the pointer back to the first data register.
+    /// No configuration other than rollover is changed.
+    ///
+    /// Call this after any method returns an I2C error, before trusting further readings.
+    pub fn recover(&mut self) -> Result<(), I::Error> {
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Recovering QMC5883L bus state");
+        self.modify_control2(|flags| flags.insert(Control2::ROL_PNT))?;
+        self.set_pointer(registers::Register16::X as u8)
+    }
+
+    /// Read the SET/RESET period register (`0x0B`).
+    ///
+    /// The datasheet recommends a value of `0x01`; it resets to `0x00`.
+    pub fn set_reset_period(&mut self) -> Result<u8, I::Error> {
+        self.read_set_reset_period()
+    }
+
+    /// Write the SET/RESET period register (`0x0B`).
+    ///
+    /// See [`Self::set_reset_period`].
+    pub fn set_set_reset_period(&mut self, period: u8) -> Result<(), I::Error> {
+        self.write_set_reset_period(period)
+    }
+
+    /// Read the SET/RESET period register (`0x0B`) as a signed value.
+    ///
+    /// This is the same byte as [`Self::set_reset_period`], reinterpreted as two's complement,
+    /// for users whose documentation describes the register as signed: eg. `0xFF` reads as `-1`.
+    /// Prefer [`Self::set_reset_period`] unless you need this interpretation.
+    pub fn set_reset_value(&mut self) -> Result<i8, I::Error> {
+        Ok(bytemuck::cast(self.read_set_reset_period()?))
+    }
+
+    /// Write the SET/RESET period register (`0x0B`) from a signed value.
+    ///
+    /// See [`Self::set_reset_value`].
+    pub fn set_set_reset_value(&mut self, val: i8) -> Result<(), I::Error> {
+        self.write_set_reset_period(bytemuck::cast(val))
+    }
+
+    /// Force a SET/RESET pulse through the sensor's coil.
+    ///
+    /// This can recover the sensing element after exposure to a strong field (eg. a nearby magnet),
+    /// which otherwise leaves the readings offset.
+    /// Unlike [`Self::reset`], which resets the device's *configuration*, this acts on the sensing
+    /// element and leaves the configuration untouched.
+    ///
+    /// The QMC5883L has no register to trigger a pulse manually: the device pulses the coil as
+    /// part of its measurement cycle, at the interval set by the SET/RESET period register
+    /// (`0x0B`), and always issues SET and RESET as a pair, so there's no separate RESET (see
+    /// [`Self::do_reset_coil`]).
+    /// This forces the shortest period (`0x01`), restarts measurement by cycling through
+    /// standby so that the pulse is issued with the next conversion, waits for that conversion
+    /// (see [`Settings::latency_us`]), then restores the previous period.
+    /// The period is restored even if the restart fails, and isn't touched at all if it's
+    /// already `0x01`, as recommended by the datasheet.
+    ///
+    /// Returns [`Error::InStandby`] without any bus access if the device is on standby, since
+    /// pulses are only issued alongside measurements: call [`Self::wake`] first.
+    pub fn do_set<D: DelayUs>(&mut self, delay: &mut D) -> Result<(), Error<I::Error>> {
+        if self.on_standby() {
+            return Err(Error::InStandby);
+        }
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Pulsing SET/RESET coil");
+        let period = self.read_set_reset_period().map_err(Error::I2c)?;
+        if period != 0x01 {
+            self.write_set_reset_period(0x01).map_err(Error::I2c)?;
+        }
+        let res = self.to_standby().and_then(|()| self.set_continuous());
+        if res.is_ok() {
+            delay.delay_us(self.settings.latency_us());
+        }
+        let restored = if period == 0x01 {
+            Ok(())
+        } else {
+            self.write_set_reset_period(period)
+        };
+        res.and(restored).map_err(Error::I2c)
+    }
+
+    /// Force a RESET pulse through the sensor's coil.
+    ///
+    /// The QMC5883L always issues SET and RESET as a pair, so there's no way to drive a RESET
+    /// on its own: this performs exactly the same sequence as [`Self::do_set`], and is provided
+    /// so that code written for parts with separate pulses reads naturally.
+    /// See [`Self::do_set`] for details and errors.
+    pub fn do_reset_coil<D: DelayUs>(&mut self, delay: &mut D) -> Result<(), Error<I::Error>> {
+        self.do_set(delay)
+    }
+
+    /// Get the current contents of the [`Control2`] register.
+    pub fn control2(&mut self) -> Result<Control2, I::Error> {
+        self.get_control2()
+    }
+
+    /// Overwrite the [`Control2`] register.
+    ///
+    /// This is a low-level method: prefer [`Self::enable_interrupt`], [`Self::disable_interrupt`]
+    /// and [`Self::reset`] where possible.
+    /// Note that the driver relies on [`Control2::ROL_PNT`] being set for its multi-byte reads.
+    pub fn set_control2(&mut self, flags: Control2) -> Result<(), I::Error> {
+        Registers::set_control2(self, flags)?;
+        if flags.contains(Control2::SOFT_RST) {
+            // The settings register is reset to zero, which is standby
+            self.mode = settings::Mode::Standby;
+        }
+        Ok(())
+    }
+
+    /// Set all of the [`Control2`] flags in a single write.
+    ///
+    /// This avoids the separate read-modify-write cycles of eg. [`Self::enable_interrupt`] and
+    /// [`Self::set_pointer_rollover`] when changing several flags at once.
+    /// Note that setting [`Control2Config::soft_reset`] resets every register, including the
+    /// [`Settings`], and that it clears itself: prefer [`Self::reset`], which reconfigures the
+    /// device afterwards.
+    pub fn apply_control2(&mut self, cfg: Control2Config) -> Result<(), I::Error> {
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Applying {:?} to control register 2", cfg);
+        self.set_control2(cfg.into())
+    }
+
+    /// Enable the interrupt pin, which signals when new data is ready.
+    pub fn enable_interrupt(&mut self) -> Result<(), I::Error> {
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Enabling interrupt pin");
+        // The flag is active-low
+        self.modify_control2(|flags| flags.remove(Control2::INT_ENB))
+    }
+
+    /// Disable the interrupt pin.
+    pub fn disable_interrupt(&mut self) -> Result<(), I::Error> {
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Disabling interrupt pin");
+        self.modify_control2(|flags| flags.insert(Control2::INT_ENB))
+    }
+
+    /// Enable or disable pointer rollover on the device.
+    ///
+    /// The other bits of the control register are preserved.
+    ///
+    /// # Warning
+    ///
+    /// This is intended for debugging the bus only.
+    /// With rollover disabled, multi-byte reads will not advance the register pointer, so
+    /// [`Self::read_all`] and the other burst reads performed by this driver **will** return
+    /// incorrect data, unless [`Self::set_explicit_reads`] is enabled.
+    /// [`Self::reset`] re-enables rollover.
+    pub fn set_pointer_rollover(&mut self, enabled: bool) -> Result<(), I::Error> {
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Setting pointer rollover to {}", enabled);
+        self.modify_control2(|flags| flags.set(Control2::ROL_PNT, enabled))
+    }
+
+    /// Read-modify-write the [`Control2`] register.
+    fn modify_control2(&mut self, f: impl FnOnce(&mut Control2)) -> Result<(), I::Error> {
+        let mut flags = self.get_control2()?;
+        // Never write back a reset request
+        flags.remove(Control2::SOFT_RST);
+        f(&mut flags);
+        self.set_control2(flags)
+    }
+
+    /// Set the "Standby" mode on the device to conserve power.
+    ///
+    /// Use [`Self::wake`] to resume measurement; changing the settings also wakes the device.
+    /// Reading data doesn't: the data registers hold the last measurement until the device is
+    /// woken.
+    pub fn to_standby(&mut self) -> Result<(), I::Error> {
+        let mut set_val: u8 = self.settings.into();
+        // unset the continuous measurement bit
+        set_val &= !Settings::MODE_MASK;
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Sending QMC5883L to standby mode");
+        self.write_raw(Settings::ADDR, set_val)?;
+        self.mode = settings::Mode::Standby;
+        Ok(())
+    }
+
+    /// Take the device out of "Standby" mode, resuming continuous measurement.
+    ///
+    /// The cached [`Settings`] are re-applied as part of this.
+    pub fn wake(&mut self) -> Result<(), I::Error> {
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Waking QMC5883L from standby mode");
+        self.set_continuous()
+    }
+
+    /// Take the device out of "Standby" mode as in [`Self::wake`], then discard the first
+    /// conversion.
+    ///
+    /// The first conversion after leaving standby may be stale or incomplete.
+    /// This waits for it to be signalled with [`Status::DRDY`], reads and discards it, then
+    /// waits for the next conversion, so the next [`Self::read_all`] gives valid data.
+    ///
+    /// This is opt-in since it adds latency: around two sample periods at the configured
+    /// [`settings::OutputDataRate`] (eg. 200ms at 10Hz), during which the status register is
+    /// polled.
+    /// The discarded sample isn't counted in [`Self::samples_read`].
+    pub fn wake_settled<D: DelayUs>(&mut self, delay: &mut D) -> Result<(), I::Error> {
+        self.wake()?;
+        let interval = self.poll_interval_us();
+        self.poll_until_ready(delay, interval)?;
+        // Reading the data clears the ready flag
+        self.bus_read(registers::Register16::X as u8, &mut [0; 6])?;
+        self.poll_until_ready(delay, interval)?;
+        Ok(())
+    }
+
+    /// Put the device in continuous measurement mode.
+    ///
+    /// This is the counterpart to [`Self::to_standby`]: the settings register is written once,
+    /// with the cached [`Settings`] and the continuous mode bits.
+    /// Unlike [`Self::change_settings`], the settings aren't checked, since they were when they
+    /// were cached.
+    pub fn set_continuous(&mut self) -> Result<(), I::Error> {
+        self.write_raw(
+            Settings::ADDR,
+            self.settings.to_raw(settings::Mode::Continuous),
+        )?;
+        self.mode = settings::Mode::Continuous;
+        Ok(())
+    }
+
+    /// Check if the device is on standby.
+    ///
+    /// The user should note that this is only tracked in software, so that checking the flag
+    /// doesn't require any bus access.
+    /// See [`Self::mode`].
+    pub fn on_standby(&self) -> bool {
+        self.mode == settings::Mode::Standby
+    }
+
+    /// Get the measurement mode the device is in, as tracked by the driver.
+    ///
+    /// Every method which writes the mode updates this, so it doesn't require any bus access;
+    /// see [`Self::read_mode`] to check the device itself.
+    pub fn mode(&self) -> settings::Mode {
+        self.mode
+    }
+
+    /// Read the measurement mode the device is actually in.
+    ///
+    /// Unlike [`Self::on_standby`], this costs a bus read, but reflects the true state of the
+    /// device, eg. after it's been reset externally or reconfigured by another bus user.
+    /// The mode tracked by the driver is not updated: see [`Self::sync`].
+    pub fn read_mode(&mut self) -> Result<settings::Mode, I::Error> {
+        let mode = settings::Mode::from(self.read_raw(Settings::ADDR)?);
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        if mode != self.mode {
+            warn!(
+                "Device is in mode {}, which differs from the tracked state",
+                mode
+            );
+        }
+        Ok(mode)
+    }
+
+    /// Re-read the device's configuration, updating the driver's cached state to match.
+    ///
+    /// The driver caches the [`Settings`] and measurement mode rather than reading them back,
+    /// so they go stale if the device is reconfigured behind its back: call this after another
+    /// bus master may have written to the device, or after a suspected brown-out, before
+    /// relying on eg. [`Self::mode`] or the unit conversions.
+    /// No registers are written.
+    /// To defer the read until the settings are next needed, see [`Self::invalidate_cache`].
+    ///
+    /// Both control registers are read: if pointer rollover has been disabled, the driver falls
+    /// back to explicit reads, as in [`Self::new`].
+    pub fn sync(&mut self) -> Result<(), I::Error> {
+        let control1 = self.read_raw(Settings::ADDR)?;
+        self.settings = Settings::from(control1);
+        self.mode = settings::Mode::from(control1);
+        self.cache_stale = false;
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!(
+            "Synced {:?} in mode {} from device",
+            self.settings, self.mode
+        );
+        self.check_rollover()
+    }
+
+    /// Check whether the device is already configured, eg. because the microcontroller reset
+    /// while the device stayed powered.
+    ///
+    /// Re-initialising with [`Self::new`] resets the device, which loses any configuration and
+    /// interrupts the data: on a warm boot, this can be skipped (see [`Self::attach`] and
+    /// [`Self::new_no_reset`]).
+    ///
+    /// This is a heuristic: the device is taken to be configured if it's measuring
+    /// continuously with valid settings, and pointer rollover is enabled.
+    /// None of these hold after a power-on or soft reset (both control registers reset to
+    /// zero), and the driver's initialisation sets all of them.
+    /// Configuration made by other firmware may also pass, so only rely on this where the
+    /// driver is the device's only user.
+    pub fn detect_warm_boot(&mut self) -> Result<bool, I::Error> {
+        let control1 = self.control1_raw()?;
+        let configured = matches!(
+            Settings::from_raw(control1),
+            Ok((_, settings::Mode::Continuous))
+        );
+        Ok(configured && self.get_control2()?.contains(Control2::ROL_PNT))
+    }
+
+    /// Read the [`Status`] register.
+    ///
+    /// The flags can be checked individually, eg. with `status.contains(Status::OVL)`.
+    /// As with any read of the register, this clears the data skip flag ([`Status::DOR`]) on
+    /// the device.
+    pub fn status(&mut self) -> Result<Status, I::Error> {
+        self.get_status()
+    }
+
+    /// Check if the device is ready to have data read off it.
+    ///
+    /// This performs a single read of the [`Status`] register and nothing else: it never
+    /// changes the power state, so it's safe to use as a pure poll, including while the device
+    /// is on standby.
+    /// Note that reading the status clears the data skip flag ([`Status::DOR`]) on the device.
+    pub fn is_ready(&mut self) -> Result<bool, I::Error> {
+        Ok(self.get_status()?.contains(Status::DRDY))
+    }
+
+    /// Read the [`Status`] register, and report what's changed since the last call.
+    ///
+    /// This lets firmware react to eg. an overflow starting, rather than inspecting raw bits:
+    /// the previous status is cached on the driver for comparison.
+    /// The data ready and data skip flags are reported whenever set.
+    ///
+    /// Unlike those flags, which the device clears when registers are read, the overflow flag
+    /// only clears when the measurement returns to range: so the overflow is reported as a
+    /// sustained condition ([`Events::overflow`]), along with the edges where it starts and
+    /// ends.
+    /// Reading the status clears [`Status::DOR`], and reading data clears [`Status::DRDY`], on
+    /// the device, so other reads between calls can hide those flags from this; the overflow
+    /// flag isn't affected.
+    pub fn poll_events(&mut self) -> Result<Events, I::Error> {
+        let status = self.get_status()?;
+        let previous = core::mem::replace(&mut self.last_status, status);
+        Ok(Events {
+            data_ready: status.contains(Status::DRDY),
+            overflow: status.contains(Status::OVL),
+            overflow_started: status.contains(Status::OVL) && !previous.contains(Status::OVL),
+            overflow_ended: !status.contains(Status::OVL) && previous.contains(Status::OVL),
+            data_skipped: status.contains(Status::DOR),
+        })
+    }
+
+    /// Check if the device is ready to have data read off it, according to the
+    /// [`ReadyStrategy`] set with [`Self::set_ready_strategy`].
+    ///
+    /// With [`ReadyStrategy::StatusBit`], this is the same as [`Self::is_ready`], and `clock` is
+    /// unused.
+    /// With [`ReadyStrategy::FixedRate`], this returns `true` once a sample period has passed
+    /// since it last did (or the first time it's called), so the data should be read whenever it
+    /// returns `true`.
+    pub fn is_ready_clocked(&mut self, clock: &impl Clock) -> Result<bool, I::Error> {
+        let ReadyStrategy::FixedRate { ticks_per_second } = self.ready_strategy else {
+            return self.is_ready();
+        };
+
+        let now = clock.now();
+        let period = u64::from(ticks_per_second) / u64::from(self.settings.odr.hz());
+        let ready = self
+            .last_ready_tick
+            .is_none_or(|last| now.saturating_sub(last) >= period);
+        if ready {
+            self.last_ready_tick = Some(now);
+        }
+        Ok(ready)
+    }
+
+    /// Read all three axes' data off the device.
+    ///
+    /// You should check with [`Self::is_ready`] before you call this.
+    ///
+    /// The axes are remapped according to [`Self::set_axis_remap`], then converted to the frame
+    /// set with [`Self::set_output_frame`].
+    ///
+    /// If the device is on standby, the data registers aren't being updated, so this will return
+    /// stale data: call [`Self::wake`] first.
+    /// A warning is logged in this case when the `defmt` feature is enabled, or
+    /// [`Error::InStandby`] is returned in strict mode (see [`Self::set_strict`]).
+    /// A warning is also logged if any axis is saturated (see [`Measurement::is_saturated`]).
+    ///
+    /// Out-of-range readings are handled according to [`Self::set_overflow_policy`], and
+    /// implausible jumps according to [`Self::set_max_delta`].
+    pub fn read_all(&mut self) -> Result<Measurement, Error<I::Error>> {
+        self.read_all_filtered(false)
+    }
+
+    /// Read all three axes' data off the device, counting any out-of-range axes.
+    ///
+    /// This is as [`Self::read_all`], but also increments the count for each axis which is out
+    /// of the configured range, before the overflow policy is applied (see
+    /// [`Self::saturation_counts`]).
+    /// This is useful for field surveys, to tell whether the range is too small.
+    pub fn read_all_checked(&mut self) -> Result<Measurement, Error<I::Error>> {
+        self.read_all_filtered(true)
+    }
+
+    /// Get the number of times each of the sensor's X, Y and Z axes has been out of range.
+    ///
+    /// Only readings taken with [`Self::read_all_checked`] are counted, so that other reads
+    /// don't pay for the check.
+    /// The axes are as reported by the device, before any remapping.
+    /// The counts saturate rather than wrapping.
+    pub fn saturation_counts(&self) -> (u32, u32, u32) {
+        let [x, y, z] = self.saturation_counts;
+        (x, y, z)
+    }
+
+    /// Make [`Self::read_all_checked`] return [`Error::NearOverflow`] when any axis exceeds the
+    /// given fraction of the configured range.
+    ///
+    /// This gives early warning that the range is too small, before readings actually clip.
+    /// The fraction is stored to the nearest thousandth, and clamped to between `0.0` and
+    /// `1.0`: see [`Self::set_overflow_margin_permille`].
+    #[cfg(feature = "float")]
+    pub fn set_overflow_margin(&mut self, fraction: f32) {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let permille = (fraction.clamp(0.0, 1.0) * 1000.0 + 0.5) as u16;
+        self.set_overflow_margin_permille(permille);
+    }
+
+    /// Make [`Self::read_all_checked`] return [`Error::NearOverflow`] when any axis exceeds the
+    /// given fraction of the configured range, in thousandths.
+    ///
+    /// The threshold is relative to [`settings::FullScale::max_counts`], so follows changes of
+    /// range: eg. `900` flags readings beyond 90% of the range.
+    /// Values above `1000` are clamped to it.
+    /// Out-of-range axes are still counted (see [`Self::saturation_counts`]) before the error is
+    /// returned.
+    /// This is disabled by default.
+    pub fn set_overflow_margin_permille(&mut self, permille: u16) {
+        self.overflow_margin = Some(permille.min(1000));
+    }
+
+    /// Stop checking readings against the overflow margin.
+    ///
+    /// See [`Self::set_overflow_margin_permille`].
+    pub fn clear_overflow_margin(&mut self) {
+        self.overflow_margin = None;
+    }
+
+    /// Reset the counts returned by [`Self::saturation_counts`] to zero.
+    pub fn reset_counts(&mut self) {
+        self.saturation_counts = [0; 3];
+    }
+
+    /// Read all three axes' data, retrying glitches as described in [`Self::read_all`].
+    fn read_all_filtered(&mut self, count: bool) -> Result<Measurement, Error<I::Error>> {
+        self.check_strict()?;
+        let data = self.read_all_once(count)?;
+        self.reject_glitches(data, count)
+    }
+
+    /// Check a processed reading against the previous one, re-reading the data while it looks
+    /// like a glitch (see [`Self::set_max_delta`]).
+    ///
+    /// Every processed read goes through here, so they all share the filter.
+    /// If `count` is set, out-of-range axes in any re-reads are counted (see
+    /// [`Self::read_all_checked`]).
+    fn reject_glitches(
+        &mut self,
+        mut data: Measurement,
+        count: bool,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let Some(max_delta) = self.max_delta else {
+            return Ok(data);
+        };
+
+        for retry in 0..=Self::GLITCH_RETRIES {
+            let glitch = self.last_reading.is_some_and(|last| {
+                (data - last)
+                    .into_iter()
+                    .any(|(_, delta)| delta.saturating_abs() > max_delta)
+            });
+            if !glitch {
+                self.last_reading = Some(data);
+                return Ok(data);
+            }
+            #[cfg(all(feature = "defmt", feature = "log"))]
+            warn!("Rejecting reading {} as a glitch, re-reading", data);
+            if retry < Self::GLITCH_RETRIES {
+                data = self.read_all_once(count)?;
+            }
+        }
+        // Don't compare against the old reading forever if the field really has jumped
+        self.last_reading = None;
+        Err(Error::Glitch)
+    }
+
+    /// Read and process all three axes' data once, as in [`Self::read_all`].
+    ///
+    /// If `count` is set, out-of-range axes are counted (see [`Self::read_all_checked`]).
+    fn read_all_once(&mut self, count: bool) -> Result<Measurement, Error<I::Error>> {
+        self.note_data_read();
+        let data = if self.explicit_reads {
+            self.read_data_explicit()
+        } else {
+            self.read_data()
+        }
+        .map_err(Error::I2c)?;
+        if count {
+            let max = self.settings.rng.max_counts();
+            let axes = <[i16; 3]>::from(Measurement::from(data));
+            for (total, val) in self.saturation_counts.iter_mut().zip(axes) {
+                if !(-max..=max).contains(&val) {
+                    *total = total.saturating_add(1);
+                }
+            }
+            if let Some(permille) = self.overflow_margin {
+                let threshold = i32::from(max) * i32::from(permille) / 1000;
+                if axes.iter().any(|val| i32::from(*val).abs() > threshold) {
+                    return Err(Error::NearOverflow);
+                }
+            }
+        }
+        self.process(data)
+    }
+
+    /// Read the six data registers off the device, without any processing.
+    ///
+    /// The bytes are returned in register order, `0x00` to `0x05`: the X, Y and Z axes, each as
+    /// a pair of bytes which the datasheet specifies as LSB first.
+    /// This is useful for running a custom fixed-point pipeline, or for hardware with an unusual
+    /// byte order: unlike [`Self::read_all`], no byte order, remapping, frame or overflow policy
+    /// is applied.
+    ///
+    /// This relies on pointer rollover, regardless of [`Self::set_explicit_reads`].
+    pub fn read_data_raw(&mut self) -> Result<[u8; 6], I::Error> {
+        self.note_data_read();
+        self.read_data_bytes()
+    }
+
+    /// Read only the X and Y axes' data off the device, for a flat (2D) compass.
+    ///
+    /// Registers `0x00` to `0x03` are read in one transaction, skipping the Z axis to save bus
+    /// bytes when reading at a high rate.
+    /// Z is ignored entirely, so this is only suitable where the device is held level.
+    /// The configured byte order is applied, but as with [`Self::read_data_raw`], no remapping,
+    /// frame or overflow policy is: the axes are the device's own.
+    pub fn read_xy(&mut self) -> Result<(i16, i16), I::Error> {
+        self.note_data_read();
+        let mut buf = [0; 4];
+        self.bus_read(registers::Register16::X as u8, &mut buf)?;
+        let order = self.byte_order;
+        Ok((order.decode(&buf[0..2]), order.decode(&buf[2..4])))
+    }
+
+    /// Read all three axes' raw data off the device as unsigned values, in the order X, Y, Z.
+    ///
+    /// This is for pipelines which process the data as unsigned offset-binary: the 16-bit
+    /// values are returned without the signed interpretation, so eg. `-1` reads as `0xffff`.
+    /// The device reports two's-complement values, so [`Self::read_all`] is the correct form
+    /// for the field direction.
+    /// As with [`Self::read_data_raw`], no remapping, frame or overflow policy is applied, but
+    /// the byte order set with [`Self::set_byte_order`] is.
+    pub fn read_all_unsigned(&mut self) -> Result<(u16, u16, u16), I::Error> {
+        let raw = self.read_data_raw()?;
+        // Reinterpret the bits, which is exactly what's wanted here
+        #[allow(clippy::cast_sign_loss)]
+        let [x, y, z] = [0, 2, 4].map(|i| self.byte_order.decode(&raw[i..i + 2]) as u16);
+        Ok((x, y, z))
+    }
+
+    /// Read all three axes' data off the device, if new data is ready.
+    ///
+    /// Unlike calling [`Self::is_ready`] then [`Self::read_all`], the status and data registers
+    /// are read in a single transaction, so the data can't change between checking the status
+    /// and reading it.
+    /// The data is processed as in [`Self::read_all`].
+    ///
+    /// If [`Self::set_explicit_reads`] is enabled, this falls back to separate reads.
+    pub fn read_if_ready(&mut self) -> Result<Option<Measurement>, Error<I::Error>> {
+        self.check_strict()?;
+        let fresh = if self.explicit_reads {
+            if self.is_ready().map_err(Error::I2c)? {
+                self.read_all()?
+            } else {
+                return Ok(None);
+            }
+        } else {
+            let (data, status) = self.read_data_and_status().map_err(Error::I2c)?;
+            if !status.contains(Status::DRDY) {
+                return Ok(None);
+            }
+            self.note_data_read();
+            let data = self.process(data)?;
+            self.reject_glitches(data, false)?
+        };
+        self.samples_read = self.samples_read.wrapping_add(1);
+        Ok(Some(fresh))
+    }
+
+    /// Read all three axes' data off the device, if new data is ready and differs from the
+    /// last reading returned by this method.
+    ///
+    /// This is for change-driven applications, to skip processing while the field is static.
+    /// The data is read as in [`Self::read_if_ready`], and compared for exact equality with the
+    /// previous reading, which is cached on the driver: any change in any axis, however small,
+    /// counts.
+    /// The first reading is always returned.
+    pub fn read_if_changed(&mut self) -> Result<Option<Measurement>, Error<I::Error>> {
+        let Some(data) = self.read_if_ready()? else {
+            return Ok(None);
+        };
+        if self.last_changed == Some(data) {
+            return Ok(None);
+        }
+        self.last_changed = Some(data);
+        Ok(Some(data))
+    }
+
+    /// Get the number of fresh samples returned by [`Self::read_if_ready`].
+    ///
+    /// This counts successful reads of new data, not attempts: polls which found no new data,
+    /// or which failed, aren't counted.
+    /// Together with a [`Clock`], this can be used to check the device is producing data at the
+    /// expected rate.
+    /// The count wraps on overflow.
+    #[must_use]
+    pub fn samples_read(&self) -> u32 {
+        self.samples_read
+    }
+
+    /// Read all three axes' data off the device, if the interrupt pin signals new data is ready.
+    ///
+    /// This checks `int_pin`, wired to the device's DRDY (interrupt) pin, instead of polling the
+    /// [`Status`] register, so no bus traffic is generated until there's data to read.
+    /// The pin is assumed to be driven high by the device when data is ready, as in the
+    /// datasheet, and goes low again once the data is read.
+    /// The interrupt must have been enabled with [`Self::enable_interrupt`] first.
+    ///
+    /// The data is processed as in [`Self::read_all`].
+    /// Returns [`Error::Pin`] if the pin can't be read.
+    pub fn read_all_on_interrupt<P: InputPin>(
+        &mut self,
+        int_pin: &mut P,
+    ) -> Result<Option<Measurement>, Error<I::Error>> {
+        if int_pin.is_high().map_err(|_| Error::Pin)? {
+            self.read_all().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Repeatedly wait for the interrupt pin to signal new data, read it and pass it to `f`.
+    ///
+    /// This is the counterpart of [`Self::sample_loop`] for boards with the device's DRDY
+    /// (interrupt) pin wired up: `pin` is polled instead of the [`Status`] register, so the bus
+    /// is only used to read the data.
+    /// The pin's level is polled, sleeping with `delay` in between: at the poll interval (see
+    /// [`Self::set_poll_interval_us`]) while waiting, and for half a sample period after each
+    /// read, since new data can't be ready sooner.
+    /// For a true edge wait which leaves the CPU free, see `on_data_ready_async` (with the
+    /// `async` feature).
+    ///
+    /// The pin is assumed to be driven high by the device when data is ready and to go low again
+    /// once the data is read, as in the datasheet.
+    /// If the pin stays high, eg. because it's inverted on the way to the microcontroller, data
+    /// is read at twice the output data rate rather than when it's ready.
+    /// The interrupt must have been enabled with [`Self::enable_interrupt`] first, or this will
+    /// wait forever.
+    ///
+    /// This only returns on error: [`Error::Pin`] if the pin can't be read, or any error from
+    /// reading the data, which is processed as in [`Self::read_all`].
+    pub fn on_data_ready<P: InputPin, D: DelayUs>(
+        &mut self,
+        pin: &mut P,
+        delay: &mut D,
+        mut f: impl FnMut(Measurement),
+    ) -> Result<(), Error<I::Error>> {
+        let interval = self.poll_interval_us();
+        loop {
+            if pin.is_high().map_err(|_| Error::Pin)? {
+                f(self.read_all()?);
+                delay.delay_us(self.sample_period_us() / 2);
+            } else {
+                delay.delay_us(interval);
+            }
+        }
+    }
+
+    /// Read all three axes' data off the device, along with the [`Status`] register.
+    ///
+    /// Registers `0x00` to `0x06` are read in a single transaction: the X, Y and Z data (each
+    /// LSB first), followed by the status.
+    /// This is useful for diagnostics, eg. to display the overflow and data skip flags alongside
+    /// the data.
+    /// Note that reading the data clears the status flags on the device.
+    /// The data is processed as in [`Self::read_all`].
+    pub fn read_all_with_status(&mut self) -> Result<(Measurement, Status), Error<I::Error>> {
+        self.check_strict()?;
+        self.note_data_read();
+        let (data, status) = self.read_data_and_status().map_err(Error::I2c)?;
+        let data = self.process(data)?;
+        Ok((self.reject_glitches(data, false)?, status))
+    }
+
+    /// Read all three axes' data off the device, along with the raw status register byte.
+    ///
+    /// As in [`Self::read_all_with_status`], registers `0x00` to `0x06` are read in a single
+    /// transaction, but nothing is interpreted: the status byte is returned as read, including
+    /// any bits undocumented on the QMC5883L (eg. on clones), and the data is returned as the
+    /// device reports it, without any of the processing of [`Self::read_all`].
+    /// This is the lowest-level combined read, for users decoding the status themselves.
+    pub fn read_all_with_raw_status(&mut self) -> Result<(Measurement, u8), Error<I::Error>> {
+        self.check_strict()?;
+        self.note_data_read();
+        let (data, status) = self.read_data_and_raw_status().map_err(Error::I2c)?;
+        Ok((Measurement::from(data), status))
+    }
+
+    /// Read all three axes' data off the device, along with whether data was skipped before it.
+    ///
+    /// Since reading any register clears [`Status::DOR`], checking it separately loses either the
+    /// flag or the data it applies to.
+    /// This reads the data and status in a single transaction, as in
+    /// [`Self::read_all_with_status`], so the flag returned belongs to the same read as the data.
+    pub fn read_all_reporting_skip(&mut self) -> Result<(Measurement, bool), Error<I::Error>> {
+        let (data, status) = self.read_all_with_status()?;
+        Ok((data, status.contains(Status::DOR)))
+    }
+
+    /// Read all three axes' data off the device, along with the raw temperature.
+    ///
+    /// The data registers are read in one transaction, then the temperature registers in a
+    /// second: pointer rollover (see [`Control2::ROL_PNT`]) wraps the pointer back to `0x00`
+    /// after the status register, so they can't be read in one.
+    /// The temperature is as returned by [`Self::get_temp`], and the data is processed as in
+    /// [`Self::read_all`].
+    ///
+    /// If [`Self::set_explicit_reads`] is enabled, every register is read separately.
+    pub fn read_all_with_temp(&mut self) -> Result<(Measurement, i16), Error<I::Error>> {
+        self.check_strict()?;
+        if self.explicit_reads {
+            let data = self.read_all()?;
+            let temp = self
+                .read_reg16_explicit(registers::Register16::TOUT)
+                .map_err(Error::I2c)?;
+            return Ok((data, temp));
+        }
+
+        self.note_data_read();
+        let (data, temp) = self.read_data_and_temp().map_err(Error::I2c)?;
+        let data = self.process(data)?;
+        Ok((self.reject_glitches(data, false)?, temp))
+    }
+
+    /// Read all three axes' data off the device, re-reading the temperature only every `every`
+    /// calls.
+    ///
+    /// Temperature changes slowly, so this saves bus bandwidth when logging at a high rate.
+    /// On the first call, and on every `every`th call after that, the data and temperature are
+    /// read together as in [`Self::read_all_with_temp`].
+    /// Other calls read only the data as in [`Self::read_all`], returning the temperature from
+    /// the last time it was read.
+    /// An `every` of `0` or `1` reads the temperature on every call.
+    pub fn read_all_with_occasional_temp(
+        &mut self,
+        every: u16,
+    ) -> Result<(Measurement, i16), Error<I::Error>> {
+        match self.cached_temp {
+            Some(temp) if self.temp_age.saturating_add(1) < every => {
+                let data = self.read_all()?;
+                self.temp_age += 1;
+                Ok((data, temp))
+            }
+            _ => {
+                let (data, temp) = self.read_all_with_temp()?;
+                self.cached_temp = Some(temp);
+                self.temp_age = 0;
+                Ok((data, temp))
+            }
+        }
+    }
+
+    /// Convert raw axis data into a [`Measurement`], applying the overflow policy, axis remapping
+    /// and frame.
+    fn process(&self, raw: registers::RawData) -> Result<Measurement, Error<I::Error>> {
+        let mut data = Measurement::from(raw);
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        if data.is_saturated() {
+            warn!("Reading {} is saturated: consider a larger range", data);
+        }
+
+        let max = self.settings.rng.max_counts();
+        match self.overflow_policy {
+            OverflowPolicy::Raw => {}
+            OverflowPolicy::Error => {
+                if data
+                    .into_iter()
+                    .any(|(_, val)| !(-max..=max).contains(&val))
+                {
+                    return Err(Error::Overflow);
+                }
+            }
+            OverflowPolicy::Clamp => {
+                data = Measurement::from(<[i16; 3]>::from(data).map(|val| val.clamp(-max, max)));
+            }
+        }
+
+        Ok(self.frame.remap().apply(self.remap.apply(data)))
+    }
+
+    /// Read all three axes' data off the device into `buf`, in the order X, Y, Z.
+    ///
+    /// This is useful for writing straight into a logging buffer.
+    /// The data is processed as in [`Self::read_all`], which is otherwise more convenient.
+    pub fn read_into(&mut self, buf: &mut [i16; 3]) -> Result<(), Error<I::Error>> {
+        *buf = self.read_all()?.into();
+        Ok(())
+    }
+
+    /// Read all three axes' data off the device if new data is ready, without blocking.
+    ///
+    /// Returns [`nb::Error::WouldBlock`] if no new data is ready yet, so this must be polled until
+    /// it returns `Ok`, eg. with [`nb::block!`].
+    /// The data is processed as in [`Self::read_all`].
+    #[cfg(feature = "nb")]
+    pub fn read_all_nb(&mut self) -> nb::Result<Measurement, Error<I::Error>> {
+        if self.is_ready().map_err(Error::I2c)? {
+            Ok(self.read_all()?)
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Read all three axes' data off the device, with the calibration applied.
+    ///
+    /// The hard-iron offsets are subtracted first, then each axis is multiplied by its scale
+    /// factor.
+    /// See [`Self::set_hard_iron`] and [`Self::set_axis_scale`].
+    ///
+    /// In auto-calibration mode, the reading is also used to refine the hard-iron offsets first:
+    /// see [`Self::enable_auto_calibrate`].
+    pub fn read_all_calibrated(&mut self) -> Result<Measurement, Error<I::Error>> {
+        let data = self.read_all()?;
+        if let Some(cal) = &mut self.auto_calibrator {
+            cal.decay(Self::AUTO_CALIBRATE_DECAY_SHIFT);
+            cal.add(data);
+            if cal.quality() >= Self::AUTO_CALIBRATE_MIN_QUALITY {
+                self.hard_iron = cal.hard_iron();
+            }
+        }
+        let offset = self.hard_iron.apply(data);
+        Ok(calibration::apply_scale(offset, self.scale))
+    }
+
+    /// Capture the current reading as the baseline for [`Self::read_relative`].
+    ///
+    /// The reading is taken as in [`Self::read_all`].
+    pub fn zero(&mut self) -> Result<(), Error<I::Error>> {
+        let data = self.read_all()?;
+        self.zero = HardIron {
+            x: data.x,
+            y: data.y,
+            z: data.z,
+        };
+        Ok(())
+    }
+
+    /// Read all three axes' data off the device, relative to the baseline captured with
+    /// [`Self::zero`].
+    ///
+    /// This is for applications which care about changes in the field, eg. detecting a passing
+    /// magnet, rather than its absolute value: unlike the hard-iron offsets, the baseline
+    /// includes the Earth's field, and is captured on demand.
+    /// Before [`Self::zero`] is called, the baseline is zero.
+    ///
+    /// The subtraction saturates at the bounds of `i16`, so a change larger than the range of an
+    /// `i16` (only possible with a baseline near one extreme and a reading near the other) is
+    /// clipped rather than wrapping.
+    pub fn read_relative(&mut self) -> Result<Measurement, Error<I::Error>> {
+        let data = self.read_all()?;
+        Ok(self.zero.apply(data))
+    }
+
+    /// Read all three axes' data off the device, along with a timestamp from `clock`.
+    ///
+    /// The timestamp is captured just after the I2C read completes.
+    /// The data is processed as in [`Self::read_all`].
+    pub fn read_stamped(
+        &mut self,
+        clock: &impl Clock,
+    ) -> Result<(u64, Measurement), Error<I::Error>> {
+        let data = self.read_all()?;
+        Ok((clock.now(), data))
+    }
+
+    /// Wait until new data is ready, then read all three axes' data off the device.
+    ///
+    /// The status register is polled at an interval derived from the configured
+    /// [`settings::OutputDataRate`].
+    pub fn read_all_when_ready<D: DelayUs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let interval = self.poll_interval_us();
+        self.poll_until_ready(delay, interval).map_err(Error::I2c)?;
+        self.read_all()
+    }
+
+    /// Check that the device is still producing data, waiting up to `max_wait_us` for it.
+    ///
+    /// The status register is polled (as in [`Self::read_all_when_ready`]) until data is ready,
+    /// returning [`Error::Stalled`] if none arrives in time.
+    /// This gives long-running firmware a hook to detect a lockup: a stalled device can often
+    /// be recovered with [`Self::reset_to_defaults`].
+    ///
+    /// Since data ready stays set until the data is read, unread data counts as the device
+    /// producing data, so call this after a read.
+    /// A `max_wait_us` of a few sample periods at the configured
+    /// [`settings::OutputDataRate`] (eg. `3_000_000 / odr.hz()`) allows for jitter without
+    /// leaving a lockup unnoticed for long.
+    /// The device must not be on standby, otherwise it'll always be reported as stalled.
+    pub fn watchdog<D: DelayUs>(
+        &mut self,
+        delay: &mut D,
+        max_wait_us: u32,
+    ) -> Result<(), Error<I::Error>> {
+        let interval = self.poll_interval_us().max(1);
+        let mut waited = 0;
+        while !self.is_ready().map_err(Error::I2c)? {
+            if waited >= max_wait_us {
+                #[cfg(all(feature = "defmt", feature = "log"))]
+                warn!("No new data after {}us, device has stalled", waited);
+                return Err(Error::Stalled);
+            }
+            delay.delay_us(interval);
+            waited += interval;
+        }
+        Ok(())
+    }
+
+    /// Wake the device, take a single reading, then put it back on standby.
+    ///
+    /// This is the power-optimal pattern for infrequent readings, eg. on coin-cell devices: the
+    /// device only measures for as long as it takes to produce one sample.
+    /// The cost is latency: each call waits for a fresh conversion, which takes up to
+    /// [`Settings::latency_us`] (longer with heavier oversampling).
+    ///
+    /// If data from before the device was put on standby is still unread, it's discarded so
+    /// that only fresh data is returned.
+    /// The device is put back on standby even if reading fails.
+    pub fn read_once_low_power<D: DelayUs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<I::Error>> {
+        self.wake().map_err(Error::I2c)?;
+        let res = self.read_fresh(delay);
+        self.to_standby().map_err(Error::I2c)?;
+        res
+    }
+
+    /// Discard any stale data, then wait for and read a new sample.
+    fn read_fresh<D: DelayUs>(&mut self, delay: &mut D) -> Result<Measurement, Error<I::Error>> {
+        if self.is_ready().map_err(Error::I2c)? {
+            // Reading the data clears the ready flag
+            self.read_data().map_err(Error::I2c)?;
+        }
+        self.read_all_when_ready(delay)
+    }
+
+    /// Take `n` samples and return their mean, discarding any which overflowed.
+    ///
+    /// Each sample is waited for as in [`Self::read_all_when_ready`].
+    /// Returns [`Error::Overflow`] if no samples could be used (ie. they all overflowed, or `n` is
+    /// zero).
+    pub fn read_averaged<D: DelayUs>(
+        &mut self,
+        n: u8,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let interval = self.poll_interval_us();
+        // Sum into wider accumulators so we can't wrap
+        let mut sums = [0i32; 3];
+        let mut count = 0;
+
+        for _ in 0..n {
+            let status = self.poll_until_ready(delay, interval).map_err(Error::I2c)?;
+            let data = match self.read_all() {
+                // Rejected by the overflow policy, so discard it like any other overflow
+                Err(Error::Overflow) => continue,
+                res => res?,
+            };
+            if status.contains(Status::OVL) {
+                #[cfg(all(feature = "defmt", feature = "log"))]
+                debug!("Discarding overflowed sample {:?}", data);
+                continue;
+            }
+            for (sum, (_, val)) in sums.iter_mut().zip(data) {
+                *sum += i32::from(val);
+            }
+            count += 1;
+        }
+
+        if count == 0 {
+            return Err(Error::Overflow);
+        }
+        // The mean of a set of i16s always fits in an i16
+        #[allow(clippy::cast_possible_truncation)]
+        let mean = sums.map(|sum| (sum / count) as i16);
+        Ok(Measurement::from(mean))
+    }
+
+    /// Take `n` samples and measure how much the field varied between them.
+    ///
+    /// The result is the sum of the variances of each axis, in counts squared: a small value
+    /// means the field is stable, so eg. a heading can be trusted.
+    /// Only integer arithmetic is used.
+    ///
+    /// As a rough guide, a sensor at rest in the 2G range usually gives values in the low
+    /// thousands, which the sensor noise alone accounts for, while moving it gives values many
+    /// orders of magnitude larger.
+    /// A suitable threshold depends on the [`Settings`] and the environment, so should be
+    /// determined on the target hardware.
+    ///
+    /// Each sample is waited for as in [`Self::read_all_when_ready`].
+    /// Fewer than two samples can't vary, so give `0`.
+    pub fn stability<D: DelayUs>(&mut self, n: u8, delay: &mut D) -> Result<u32, Error<I::Error>> {
+        // The sum of up to 255 squared i16s fits comfortably in an i64
+        let mut sums = [0i64; 3];
+        let mut sums_sq = [0i64; 3];
+
+        for _ in 0..n {
+            let data = self.read_all_when_ready(delay)?;
+            for ((sum, sum_sq), (_, val)) in sums.iter_mut().zip(sums_sq.iter_mut()).zip(data) {
+                let val = i64::from(val);
+                *sum += val;
+                *sum_sq += val * val;
+            }
+        }
+
+        if n < 2 {
+            return Ok(0);
+        }
+        let n = i64::from(n);
+        let total: i64 = sums
+            .iter()
+            .zip(sums_sq)
+            .map(|(sum, sum_sq)| (sum_sq - sum * sum / n) / n)
+            .sum();
+        Ok(u32::try_from(total).unwrap_or(u32::MAX))
+    }
+
+    /// Check whether the device has settled, thermally and magnetically, eg. after power-on.
+    ///
+    /// Readings taken while the device is still warming up drift, so shouldn't be relied on.
+    /// This reads the temperature, measures the [`Self::stability`] of
+    /// [`Self::SETTLE_SAMPLES`] samples, then reads the temperature again.
+    /// The device has settled if the variance is at most [`Self::SETTLE_MAX_VARIANCE`], and the
+    /// temperature changed by at most [`Self::SETTLE_MAX_TEMP_DRIFT`] over the window.
+    /// The window lasts [`Self::SETTLE_SAMPLES`] sample periods at the configured
+    /// [`settings::OutputDataRate`]: eg. 1.6s at 10Hz.
+    ///
+    /// The thresholds suit a stationary device in the 2G range: as with [`Self::stability`],
+    /// other conditions may need a custom check.
+    pub fn is_settled<D: DelayUs>(&mut self, delay: &mut D) -> Result<bool, Error<I::Error>> {
+        let start_temp = self.get_temp().map_err(Error::I2c)?;
+        let variance = self.stability(Self::SETTLE_SAMPLES, delay)?;
+        let end_temp = self.get_temp().map_err(Error::I2c)?;
+        let drift = end_temp.saturating_sub(start_temp).saturating_abs();
+        Ok(variance <= Self::SETTLE_MAX_VARIANCE && drift <= Self::SETTLE_MAX_TEMP_DRIFT)
+    }
+
+    /// Measure the rate at which the device actually produces data, in Hz.
+    ///
+    /// This waits for a first reading, then times how long it takes for `samples` more to
+    /// become ready (busy-polling the status register), so it blocks for roughly `samples`
+    /// sample periods.
+    /// `clock` must tick `ticks_per_second` times per second.
+    /// This is useful for checking [`Settings::effective_odr`] on real hardware.
+    #[cfg(feature = "float")]
+    pub fn measure_rate(
+        &mut self,
+        samples: u16,
+        clock: &impl Clock,
+        ticks_per_second: u32,
+    ) -> Result<f32, Error<I::Error>> {
+        let wait_and_read = |mag: &mut Self| -> Result<(), Error<I::Error>> {
+            while !mag.is_ready().map_err(Error::I2c)? {}
+            mag.read_all().map(|_| ())
+        };
+
+        wait_and_read(self)?;
+        let start = clock.now();
+        for _ in 0..samples {
+            wait_and_read(self)?;
+        }
+        let elapsed = clock.now().saturating_sub(start).max(1);
+        // Precision loss is fine for a rate estimate
+        #[allow(clippy::cast_precision_loss)]
+        let seconds = elapsed as f32 / ticks_per_second as f32;
+        Ok(f32::from(samples) / seconds)
+    }
+
+    /// Wait for up to `count` new readings, pushing each one into `out`.
+    ///
+    /// Each reading is waited for as in [`Self::read_all_when_ready`].
+    /// If `out` fills up before `count` readings have been taken, this stops early without
+    /// waiting for any more data: the readings already in `out` are kept.
+    /// Returns the number of readings pushed.
+    #[cfg(feature = "heapless")]
+    pub fn read_batch<D: DelayUs, const N: usize>(
+        &mut self,
+        out: &mut heapless::Vec<Measurement, N>,
+        count: usize,
+        delay: &mut D,
+    ) -> Result<usize, Error<I::Error>> {
+        let mut pushed = 0;
+        while pushed < count && !out.is_full() {
+            let data = self.read_all_when_ready(delay)?;
+            // Can't fail, we checked there's room
+            let _ = out.push(data);
+            pushed += 1;
+        }
+        Ok(pushed)
+    }
+
+    /// Repeatedly wait for new data, read it and pass it to `f`, `n` times.
+    ///
+    /// Samples are taken at the configured [`settings::OutputDataRate`].
+    /// Pass [`usize::MAX`] as `n` to sample (practically) forever.
+    ///
+    /// The second argument to `f` is `true` if the device reported that data was skipped before
+    /// this sample (ie. samples were dropped because they weren't read quickly enough).
+    /// These occurrences are also counted in the returned [`SampleStats`].
+    pub fn sample_loop<D: DelayUs>(
+        &mut self,
+        delay: &mut D,
+        n: usize,
+        mut f: impl FnMut(Measurement, bool),
+    ) -> Result<SampleStats, Error<I::Error>> {
+        let interval = self.poll_interval_us();
+        let mut totals = SampleStats::default();
+        for _ in 0..n {
+            let status = self.poll_until_ready(delay, interval).map_err(Error::I2c)?;
+            let data = self.read_all()?;
+            let skipped = status.contains(Status::DOR);
+            totals.read += 1;
+            totals.skipped += usize::from(skipped);
+            f(data, skipped);
+        }
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        if totals.skipped > 0 {
+            warn!(
+                "Data skipped {} times in {} samples",
+                totals.skipped, totals.read
+            );
+        }
+        Ok(totals)
+    }
+
+    /// Enable or disable strict mode, which turns the standby pitfalls into errors.
+    ///
+    /// Normally, data reads return stale data while the device is on standby, and
+    /// [`Self::change_settings`] silently wakes it.
+    /// In strict mode, these return [`Error::InStandby`] without any bus access instead, so the
+    /// device's power state only changes when explicitly requested, eg. with [`Self::wake`].
+    /// Disabled by default.
+    ///
+    /// The check covers:
+    /// - [`Self::read`], [`Self::read_all`] and every method built on it
+    /// - [`Self::read_if_ready`] and the methods built on it, such as
+    ///   [`Self::read_if_changed`]
+    /// - [`Self::read_all_with_status`], [`Self::read_all_with_raw_status`] and
+    ///   [`Self::read_all_with_temp`]
+    /// - [`Self::change_settings`] and [`Self::change_settings_clamped`]
+    ///
+    /// The low-level reads which return bare bus errors, such as [`Self::read_data_raw`],
+    /// [`Self::read_xy`], [`Self::read_axes`] and [`Self::snapshot`], aren't checked.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Check whether strict mode is enabled.
+    ///
+    /// See [`Self::set_strict`].
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Return [`Error::InStandby`] if the device is on standby in strict mode.
+    fn check_strict(&self) -> Result<(), Error<I::Error>> {
+        if self.strict && self.on_standby() {
+            return Err(Error::InStandby);
+        }
+        Ok(())
+    }
+
+    /// Warn before a data read if the device isn't measuring, so the data will be stale.
+    #[allow(clippy::unused_self)]
+    fn note_data_read(&self) {
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        if self.mode != settings::Mode::Continuous {
+            warn!("Reading data while not measuring, the data will be stale: call `wake` first");
+        }
+    }
+
+    /// The time between samples at the configured [`settings::OutputDataRate`].
+    fn sample_period_us(&self) -> u32 {
+        1_000_000 / self.settings.odr.hz()
+    }
+
+    /// The interval at which to poll the status register while waiting for data.
+    fn poll_interval_us(&self) -> u32 {
+        // By default, poll ten times per sample period
+        self.poll_interval
+            .unwrap_or_else(|| self.sample_period_us() / 10)
+    }
+
+    /// Poll the status register until data is ready, returning the final status.
+    fn poll_until_ready<D: DelayUs>(
+        &mut self,
+        delay: &mut D,
+        interval_us: u32,
+    ) -> Result<Status, I::Error> {
+        loop {
+            let status = self.get_status()?;
+            if status.contains(Status::DRDY) {
+                return Ok(status);
+            }
+            delay.delay_us(interval_us);
+        }
+    }
+
+    /// Read all three axes' data off the device, as a percentage of the configured
+    /// [`settings::FullScale`].
+    ///
+    /// Values approaching ±100% indicate that the field is about to overflow the current range.
+    #[cfg(feature = "float")]
+    pub fn read_all_percent(&mut self) -> Result<(f32, f32, f32), Error<I::Error>> {
+        let data = self.read_all()?;
+        let rng = self.settings.rng;
+        let full_scale = rng.max_field_gauss() * rng.scale_factor();
+        let percent = |val: i16| f32::from(val) * 100.0 / full_scale;
+        Ok((percent(data.x), percent(data.y), percent(data.z)))
+    }
+
+    /// Read all three axes' data off the device, converted to Gauss.
+    ///
+    /// The conversion uses the sensitivity of the cached [`settings::FullScale`] (see
+    /// [`Self::set_sensitivity`]).
+    #[cfg(feature = "float")]
+    pub fn read_all_gauss(&mut self) -> Result<(f32, f32, f32), Error<I::Error>> {
+        let data = self.read_all()?;
+        let scale = self.sensitivity(self.settings.rng);
+        let gauss = |val: i16| f32::from(val) / scale;
+        Ok((gauss(data.x), gauss(data.y), gauss(data.z)))
+    }
+
+    /// Read all three axes' data off the device, with the least oversampling which gives the
+    /// requested precision.
+    ///
+    /// The [`settings::OverSampleRatio`] is picked as the lowest whose
+    /// [`Settings::noise_floor_gauss`] is no more than `target_noise_gauss`, trading power for
+    /// precision.
+    /// If it differs from the current one, the new settings are applied and this waits for them
+    /// to take effect, as in [`Self::change_settings_settled`]; the
+    /// [`settings::OutputDataRate`] is lowered if the oversampling can't support it (see
+    /// [`Settings::clamped`]).
+    /// The new settings are left in place afterwards, so repeated calls with the same target
+    /// don't pay the settling time again.
+    /// The data is then read once it's ready, as in [`Self::read_all_when_ready`].
+    ///
+    /// Returns [`Error::Unattainable`] without any bus access if even the highest oversampling
+    /// is too noisy: a more sensitive [`settings::FullScale`] may help.
+    #[cfg(feature = "float")]
+    pub fn read_all_at_precision<D: DelayUs>(
+        &mut self,
+        target_noise_gauss: f32,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<I::Error>> {
+        use settings::OverSampleRatio;
+
+        let set = [
+            OverSampleRatio::OSR64,
+            OverSampleRatio::OSR128,
+            OverSampleRatio::OSR256,
+            OverSampleRatio::OSR512,
+        ]
+        .into_iter()
+        .map(|osr| Settings {
+            osr,
+            ..self.settings
+        })
+        .find(|set| set.noise_floor_gauss() <= target_noise_gauss)
+        .ok_or(Error::Unattainable)?
+        .clamped();
+        if set != self.settings {
+            self.change_settings_settled(set, delay)?;
+        }
+        self.read_all_when_ready(delay)
+    }
+
+    /// Read all three axes' data off the device, converted to the given [`Units`].
+    ///
+    /// This is useful where the units are chosen at runtime, eg. by the user.
+    /// The conversion uses the cached [`settings::FullScale`].
+    /// For the counts as integers, use [`Self::read_all`].
+    #[cfg(feature = "float")]
+    pub fn read_all_in(&mut self, units: Units) -> Result<(f32, f32, f32), Error<I::Error>> {
+        let data = self.read_all()?;
+        let per_count = units.per_count_with(self.sensitivity(self.settings.rng));
+        let convert = |val: i16| f32::from(val) * per_count;
+        Ok((convert(data.x), convert(data.y), convert(data.z)))
+    }
+
+    /// Read all three axes' data off the device, compensated for temperature drift.
+    ///
+    /// The data and temperature are read together as in [`Self::read_all_with_temp`], then each
+    /// axis is divided by `1 + coefficient * (temp - reference)`, using the values set with
+    /// [`Self::set_temp_coefficient`] and [`Self::set_reference_temp`].
+    /// With the default coefficient of `0.0`, the data is returned unchanged (in counts).
+    ///
+    /// Most users won't need this: the drift is small, so it only matters for precision
+    /// applications with a coefficient calibrated for their part.
+    #[cfg(feature = "float")]
+    pub fn read_all_corrected(&mut self) -> Result<(f32, f32, f32), Error<I::Error>> {
+        let (data, temp) = self.read_all_with_temp()?;
+        let delta_c = f32::from(temp.saturating_sub(self.reference_temp)) / Self::TEMP_COUNTS_PER_C;
+        let factor = 1.0 + self.temp_coefficient * 1e-6 * delta_c;
+        let correct = |val: i16| f32::from(val) / factor;
+        Ok((correct(data.x), correct(data.y), correct(data.z)))
+    }
+
+    /// Set the sensitivity drift with temperature used by [`Self::read_all_corrected`], in
+    /// parts per million per °C.
+    ///
+    /// `0.0`, the default, disables the correction.
+    #[cfg(feature = "float")]
+    pub fn set_temp_coefficient(&mut self, ppm_per_c: f32) {
+        self.temp_coefficient = ppm_per_c;
+    }
+
+    /// Set the temperature at which [`Self::read_all_corrected`] applies no correction, in raw
+    /// counts as returned by [`Self::get_temp`].
+    ///
+    /// The temperature sensor's offset isn't calibrated, so capture this from the device itself
+    /// (eg. with [`Self::average_temperature`]) when calibrating.
+    /// Defaults to `0`.
+    #[cfg(feature = "float")]
+    pub fn set_reference_temp(&mut self, raw: i16) {
+        self.reference_temp = raw;
+    }
+
+    /// Read all three axes' data off the device, normalised to a unit vector.
+    ///
+    /// This is useful where only the direction of the field matters, eg. as the magnetic reference
+    /// for orientation filters.
+    /// Returns [`Error::Overflow`] if any axis is saturated, since the direction would then be
+    /// wrong, and [`Error::ZeroField`] if all axes read zero.
+    #[cfg(feature = "float")]
+    pub fn read_unit_vector(&mut self) -> Result<(f32, f32, f32), Error<I::Error>> {
+        let data = self.read_all()?;
+        if data.is_saturated() {
+            return Err(Error::Overflow);
+        }
+        data.unit_vector().ok_or(Error::ZeroField)
+    }
+
+    /// Read all three axes' data off the device, and calculate the angle between the field and
+    /// `reference`, in degrees in the range `[0, 180]`.
+    ///
+    /// This is useful for guiding a user to re-orient the device towards a direction captured
+    /// earlier (eg. with [`Self::read_all`]): the angle falls to zero as they line up.
+    /// Returns [`Error::ZeroField`] if either the reading or `reference` has zero magnitude.
+    /// See [`Measurement::angle_to`].
+    #[cfg(feature = "float")]
+    pub fn angle_to(&mut self, reference: Measurement) -> Result<f32, Error<I::Error>> {
+        let data = self.read_all()?;
+        data.angle_to(reference).ok_or(Error::ZeroField)
+    }
+
+    /// Read a particular axis' data, converted to Gauss.
+    ///
+    /// The conversion uses the sensitivity of the cached [`settings::FullScale`] (see
+    /// [`Self::set_sensitivity`]).
+    #[cfg(feature = "float")]
+    pub fn read_gauss(&mut self, axis: Axis) -> Result<f32, I::Error> {
+        let val = self.read_axis(axis)?;
+        Ok(f32::from(val) / self.sensitivity(self.settings.rng))
+    }
+
+    /// Override the sensitivity of a range, in counts (LSB) per Gauss.
+    ///
+    /// Some clones have a different sensitivity from the datasheet, so read systematically
+    /// off-scale: this can be calibrated by measuring a known reference field.
+    /// The override affects every conversion the driver makes to Gauss or other physical units
+    /// ([`Self::read_all_gauss`], [`Self::read_gauss`], [`Self::read_all_in`] and so on), but
+    /// not the standalone conversions which take a [`settings::FullScale`], such as
+    /// [`Measurement::to_milligauss`] and [`Units::per_count`].
+    /// Defaults to [`settings::FullScale::scale_factor`].
+    #[cfg(feature = "float")]
+    pub fn set_sensitivity(&mut self, rng: settings::FullScale, lsb_per_gauss: f32) {
+        self.sensitivity[rng as usize] = lsb_per_gauss;
+    }
+
+    /// Get the sensitivity used for a range, in counts (LSB) per Gauss.
+    ///
+    /// See [`Self::set_sensitivity`].
+    #[cfg(feature = "float")]
+    pub fn sensitivity(&self, rng: settings::FullScale) -> f32 {
+        self.sensitivity[rng as usize]
+    }
+
+    /// Read a particular axis' data.
+    ///
+    /// You should check with [`Self::is_ready`] before you call this.
+    ///
+    /// As with [`Self::read_all`], this will return stale data if the device is on standby (or
+    /// [`Error::InStandby`] in strict mode), and a warning is logged if the reading is saturated.
+    pub fn read(&mut self, axis: Axis) -> Result<i16, Error<I::Error>> {
+        self.check_strict()?;
+        self.read_axis(axis).map_err(Error::I2c)
+    }
+
+    /// Read a particular axis' data, as in [`Self::read`] but ignoring strict mode.
+    fn read_axis(&mut self, axis: Axis) -> Result<i16, I::Error> {
+        self.note_data_read();
+        let val = if self.explicit_reads {
+            self.read_reg16_explicit(axis.into())?
+        } else {
+            self.read_reg16(axis.into())?
+        };
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        if val == i16::MIN || val == i16::MAX {
+            warn!(
+                "Reading {} on axis {} is saturated: consider a larger range",
+                val, axis
+            );
+        }
+        Ok(val)
+    }
+
+    /// Read several axes' data, in the order given.
+    ///
+    /// When the axes are contiguous and in register order (eg. `[Axis::Y, Axis::Z]`), they're
+    /// read in a single transaction using pointer rollover.
+    /// Otherwise (eg. `[Axis::Z, Axis::X]`), or if [`Self::set_explicit_reads`] is enabled, each
+    /// axis is read separately as with [`Self::read`].
+    ///
+    /// As with [`Self::read`], no remapping or other processing is applied.
+    pub fn read_axes<const N: usize>(&mut self, axes: [Axis; N]) -> Result<[i16; N], I::Error> {
+        let index = |axis: Axis| registers::Register16::from(axis) as u8;
+        let mut out = [0; N];
+        let contiguous = axes.windows(2).all(|w| index(w[1]) == index(w[0]) + 2);
+
+        match axes.first() {
+            Some(&first) if contiguous && !self.explicit_reads => {
+                self.note_data_read();
+                self.read_reg16_span(first.into(), &mut out)?;
+            }
+            _ => {
+                for (val, axis) in out.iter_mut().zip(axes) {
+                    *val = self.read_axis(axis)?;
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Read all three axes' data, returned in the order given.
+    ///
+    /// Unlike [`Self::read_axes`], the bus read is always the same contiguous span of all six
+    /// data registers, whatever the order (unless [`Self::set_explicit_reads`] is enabled): the
+    /// values are permuted afterwards, so any order costs a single transaction.
+    /// Axes may be repeated, eg. `[Axis::Z, Axis::Z, Axis::X]`.
+    ///
+    /// As with [`Self::read`], no remapping or other processing is applied.
+    pub fn read_ordered(&mut self, order: [Axis; 3]) -> Result<[i16; 3], I::Error> {
+        let [x, y, z] = self.read_axes([Axis::X, Axis::Y, Axis::Z])?;
+        Ok(order.map(|axis| match axis {
+            Axis::X => x,
+            Axis::Y => y,
+            Axis::Z => z,
+        }))
+    }
+
+    /// Read all three axes, and return the one which sees the strongest field.
+    ///
+    /// At rest, this is the axis most closely aligned with the local field, so it's a quick
+    /// bring-up sanity check that the channels are alive and distinguishable: eg. compare it
+    /// against the expected mounting orientation.
+    /// It's a diagnostic, not a precise orientation.
+    /// Returns [`Error::ZeroField`] if every axis reads zero, which suggests a dead sensor.
+    pub fn orientation_hint(&mut self) -> Result<Axis, Error<I::Error>> {
+        let data = self.read_all()?;
+        if data == Measurement::default() {
+            return Err(Error::ZeroField);
+        }
+        Ok(data
+            .into_iter()
+            .max_by_key(|(_, val)| val.unsigned_abs())
+            .map_or(Axis::X, |(axis, _)| axis))
+    }
+
+    /// Perform a basic go/no-go self-test of the sensor.
+    ///
+    /// The sequence is:
+    ///
+    /// 1. Take a reading, which must not contain any saturated (`i16::MIN`/`i16::MAX`) axes, and
+    ///    must not be entirely zero (which would indicate dead channels)
+    /// 2. Disable the periodic SET/RESET of the sensor (register `0x0B`) for two sample periods
+    /// 3. Restore the SET/RESET period and wait another two sample periods
+    /// 4. Take a second reading, each axis of which must be within
+    ///    [`Self::SELF_TEST_TOLERANCE`] counts of the first
+    ///
+    /// This doesn't test the sensing element's response: the QMC5883L can't apply a known test
+    /// field, so this only checks that the channels are alive and that the readings are
+    /// consistent, coming back to where they were after the SET/RESET cycle is interrupted.
+    /// A sensor which fails it is likely faulty, but passing doesn't prove the sensitivity is
+    /// correct.
+    /// The device should be held still for the duration of the check.
+    ///
+    /// The SET/RESET period is restored whatever happens, including if a read fails part-way
+    /// through.
+    /// Returns [`Error::InStandby`] without any bus access if the device is on standby, since no
+    /// new data would ever be ready: call [`Self::wake`] first.
+    pub fn self_test<D: DelayUs>(&mut self, delay: &mut D) -> Result<bool, Error<I::Error>> {
+        if self.on_standby() {
+            return Err(Error::InStandby);
+        }
+        let period = self.read_set_reset_period().map_err(Error::I2c)?;
+        let before = self.read_all_when_ready(delay)?;
+
+        if before.is_saturated() || before == Measurement::default() {
+            #[cfg(all(feature = "defmt", feature = "log"))]
+            warn!("Self-test failed: bad initial reading {:?}", before);
+            return Ok(false);
+        }
+
+        self.write_set_reset_period(0).map_err(Error::I2c)?;
+        let disabled = (0..2).try_for_each(|_| self.read_all_when_ready(delay).map(|_| ()));
+        let restored = self.write_set_reset_period(period).map_err(Error::I2c);
+        disabled?;
+        restored?;
+        for _ in 0..2 {
+            self.read_all_when_ready(delay)?;
+        }
+
+        let after = self.read_all_when_ready(delay)?;
+        let passed = (after - before)
+            .into_iter()
+            .all(|(_, diff)| diff.saturating_abs() <= Self::SELF_TEST_TOLERANCE);
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        if !passed {
+            warn!("Self-test failed: {:?} differs from {:?}", after, before);
+        }
+        Ok(passed)
+    }
+
+    /// Read all axes and calculate a heading, in degrees in the range `[0, 360)`.
+    ///
+    /// This assumes the sensor is held level: see [`Self::heading_with_tilt`] otherwise.
+    /// The hard-iron offsets and declination are applied.
+    #[cfg(feature = "float")]
+    pub fn heading(&mut self) -> Result<f32, Error<I::Error>> {
+        let data = self.read_all_calibrated()?;
+        let heading = heading::tilt_compensated(self.to_ned(data), 0.0, 0.0);
+        Ok(heading::normalise_degrees(heading + self.declination))
+    }
+
+    /// Read all axes, and express the field in spherical coordinates: `(magnitude, azimuth,
+    /// inclination)`.
+    ///
+    /// - The magnitude is the total field strength, in Gauss (see [`Self::read_all_gauss`]).
+    /// - The azimuth is the heading, in degrees in the range `[0, 360)`, as in [`Self::heading`]
+    ///   (so the declination is applied, and the sensor is assumed to be level).
+    /// - The inclination is the angle of the field below the horizontal plane, in degrees in
+    ///   the range `[-90, 90]`: it's positive when the field points down, as in the northern
+    ///   hemisphere (the sensor's Z axis points up, so the reading is converted to
+    ///   North-East-Down as in [`Self::heading_with_tilt`]).
+    ///
+    /// All three are calculated from a single reading, calibrated as in
+    /// [`Self::read_all_calibrated`].
+    #[cfg(feature = "float")]
+    pub fn read_spherical(&mut self) -> Result<(f32, f32, f32), Error<I::Error>> {
+        let data = self.read_all_calibrated()?;
+        let magnitude = data.magnitude() / self.sensitivity(self.settings.rng);
+        let ned = self.to_ned(data);
+        let azimuth =
+            heading::normalise_degrees(heading::tilt_compensated(ned, 0.0, 0.0) + self.declination);
+        Ok((magnitude, azimuth, heading::inclination(ned)))
+    }
+
+    /// Read all axes, and get which of `sectors` equal sectors of the compass the heading falls
+    /// in, eg. with `16` for a 16-point compass rose.
+    ///
+    /// Sector `i` is centred on the heading `i * 360 / sectors` degrees, so sector `0` is centred
+    /// on north and spans half a sector either side of it; a heading exactly on a boundary
+    /// rounds up into the next sector.
+    /// The heading is calculated with [`Measurement::heading_centidegrees`], so unlike
+    /// `heading` no floating-point maths is needed, and the declination isn't applied.
+    /// The calibration is applied as in [`Self::read_all_calibrated`].
+    /// A `sectors` of `0` is treated as `1`.
+    pub fn heading_sectors(&mut self, sectors: u16) -> Result<u16, Error<I::Error>> {
+        let data = self.read_all_calibrated()?;
+        let heading = u32::from(self.frame.to_sensor(data).heading_centidegrees());
+        let sectors = u32::from(sectors.max(1));
+        // Less than `sectors`, so always fits
+        #[allow(clippy::cast_possible_truncation)]
+        let sector = ((heading * sectors + 18_000) / 36_000 % sectors) as u16;
+        Ok(sector)
+    }
+
+    /// Read all axes and get the [`Cardinal`] direction of the heading.
+    ///
+    /// To stop the output flickering when the heading sits on the boundary between two sectors,
+    /// the previously reported direction is held until the heading moves more than a margin past
+    /// the edge of its sector.
+    /// The margin defaults to [`Self::DEFAULT_CARDINAL_MARGIN`]: see
+    /// [`Self::set_cardinal_margin`] to tune it.
+    #[cfg(feature = "float")]
+    pub fn cardinal(&mut self) -> Result<Cardinal, Error<I::Error>> {
+        let heading = self.heading()?;
+        let next = Cardinal::from_heading(heading);
+        let limit = Cardinal::SECTOR_WIDTH / 2.0 + self.cardinal_margin;
+        let reported = match self.last_cardinal {
+            Some(prev) if heading::angle_between(heading, prev.centre()) <= limit => prev,
+            _ => next,
+        };
+        self.last_cardinal = Some(reported);
+        Ok(reported)
+    }
+
+    /// Set the hysteresis margin used by [`Self::cardinal`], in degrees.
+    ///
+    /// Raise this if the output still flickers with a noisy sensor, at the cost of a slower
+    /// response to real turns; `0.0` disables the hysteresis.
+    /// The margin should be less than half of [`Cardinal::SECTOR_WIDTH`].
+    #[cfg(feature = "float")]
+    pub fn set_cardinal_margin(&mut self, margin: f32) {
+        self.cardinal_margin = margin;
+    }
+
+    /// Read all axes and calculate a tilt-compensated heading, in degrees in the range `[0, 360)`.
+    ///
+    /// The pitch and roll are taken from `src`: see [`TiltSource`] for the axis conventions, which
+    /// the sensor and `src` must both be aligned to.
+    /// The hard-iron offsets and declination are applied.
+    #[cfg(feature = "float")]
+    pub fn heading_with_tilt(&mut self, src: &impl TiltSource) -> Result<f32, Error<I::Error>> {
+        let data = self.read_all_calibrated()?;
+        let heading = heading::tilt_compensated(self.to_ned(data), src.pitch(), src.roll());
+        Ok(heading::normalise_degrees(heading + self.declination))
+    }
+
+    /// Convert a reading in the output frame to North-East-Down, for the heading calculations.
+    ///
+    /// The sensor's X axis is taken to point forwards, as in [`Frame`].
+    #[cfg(feature = "float")]
+    fn to_ned(&self, m: Measurement) -> Measurement {
+        Frame::Ned.remap().apply(self.frame.to_sensor(m))
+    }
+
+    /// Set the hard-iron offsets, used by [`Self::read_all_calibrated`].
+    ///
+    /// The offsets are subtracted after any axis remapping, so should be given in the remapped
+    /// frame (ie. as measured with [`Self::read_all`]).
+    pub fn set_hard_iron(&mut self, hard_iron: HardIron) {
+        self.hard_iron = hard_iron;
+    }
+
+    /// Get the current hard-iron offsets.
+    pub fn hard_iron(&self) -> HardIron {
+        self.hard_iron
+    }
+
+    /// Enable or disable auto-calibration mode.
+    ///
+    /// In this mode, every reading from [`Self::read_all_calibrated`] is fed into an internal
+    /// [`Calibrator`], and the hard-iron offsets are refined from it as the device is moved
+    /// through new orientations.
+    /// The offsets are only updated once the readings cover enough orientations to be trusted
+    /// (see [`Self::AUTO_CALIBRATE_MIN_QUALITY`]), and the calibrator slowly forgets old extremes
+    /// (see [`Self::AUTO_CALIBRATE_DECAY_SHIFT`]), so it can follow gradual changes.
+    ///
+    /// This is convenient for long-running devices which are moved around regularly, but the
+    /// offsets will drift if the device stays in a few orientations for a long time, or if the
+    /// magnetic environment changes suddenly.
+    /// For a fixed installation, prefer a one-off calibration with [`Calibrator`].
+    ///
+    /// Enabling starts from an empty calibrator, but keeps the current offsets until new ones
+    /// can be estimated; enabling again while already enabled keeps the calibrator's readings.
+    /// Disabling discards the calibrator, but keeps the current offsets.
+    pub fn enable_auto_calibrate(&mut self, enabled: bool) {
+        self.auto_calibrator = if enabled {
+            Some(self.auto_calibrator.unwrap_or_default())
+        } else {
+            None
+        };
+    }
+
+    /// Run a guided calibration, taking `samples` readings while the user rotates the device.
+    ///
+    /// Each sample is waited for as in [`Self::read_all_when_ready`] and fed into a
+    /// [`Calibrator`], then `progress` is called with its [`Calibrator::coverage`] (0 to 100),
+    /// eg. to drive a progress bar or a "keep rotating" prompt.
+    /// The resulting [`Calibration`] (with the current declination, scale and temperature
+    /// compensation) is returned, but not applied: check it, then apply it with
+    /// [`Calibration::apply_to`] or [`Self::set_hard_iron`].
+    ///
+    /// The device should be turned slowly through as many orientations as possible, eg. in a
+    /// figure of eight, for the whole run.
+    /// A few hundred samples are recommended: eg. 500 samples take 10 seconds at 50Hz, which is
+    /// enough for an unhurried rotation.
+    pub fn calibrate<D: DelayUs>(
+        &mut self,
+        samples: u16,
+        delay: &mut D,
+        mut progress: impl FnMut(u8),
+    ) -> Result<Calibration, Error<I::Error>> {
+        let mut cal = Calibrator::new();
+        for _ in 0..samples {
+            cal.add(self.read_all_when_ready(delay)?);
+            progress(cal.coverage());
+        }
+        Ok(Calibration {
+            hard_iron: cal.hard_iron(),
+            ..self.current_calibration()
+        })
+    }
+
+    /// Get the calibration currently in use, eg. to persist offsets found by auto-calibration.
+    ///
+    /// Without the `float` feature, the temperature compensation takes its defaults.
+    /// See [`Calibration::apply_to`] to restore it.
+    pub fn current_calibration(&self) -> Calibration {
+        #[allow(unused_mut)]
+        let mut cal = Calibration {
+            hard_iron: self.hard_iron,
+            declination: self.declination,
+            scale: self.scale,
+            ..Calibration::default()
+        };
+        #[cfg(feature = "float")]
+        {
+            cal.temp_coefficient = self.temp_coefficient;
+            cal.reference_temp = self.reference_temp;
+        }
+        cal
+    }
+
+    /// Set the per-axis scale factors used by [`Self::read_all_calibrated`], in the order X, Y, Z.
+    ///
+    /// These correct for gain mismatch between the axes (a basic, diagonal-only soft-iron
+    /// correction), and are applied after the hard-iron offsets are subtracted.
+    /// Defaults to `(1.0, 1.0, 1.0)`.
+    pub fn set_axis_scale(&mut self, scale: (f32, f32, f32)) {
+        self.scale = scale;
+    }
+
+    /// Get the current per-axis scale factors.
+    pub fn axis_scale(&self) -> (f32, f32, f32) {
+        self.scale
+    }
+
+    /// Set the magnetic declination at the device's location, in degrees (positive east).
+    ///
+    /// This is added to calculated headings to give a heading relative to true north.
+    /// With the `wmm-lite` feature, `declination_for` can estimate it from a position.
+    pub fn set_declination(&mut self, declination: f32) {
+        self.declination = declination;
+    }
+
+    /// Get the current magnetic declination, in degrees.
+    pub fn declination(&self) -> f32 {
+        self.declination
+    }
+
+    /// Set the axis remapping applied to every reading.
+    pub fn set_axis_remap(&mut self, remap: AxisRemap) {
+        self.remap = remap;
+    }
+
+    /// Get the current axis remapping.
+    pub fn axis_remap(&self) -> AxisRemap {
+        self.remap
+    }
+
+    /// Set which axes are negated in every reading, eg. for sensors mounted mirrored.
+    ///
+    /// This is a lightweight alternative to [`Self::set_axis_remap`] which doesn't swap any
+    /// axes.
+    /// It replaces the negation of the current remapping (keeping its permutation), so the
+    /// axes refer to the *output* axes, and a later call to [`Self::set_axis_remap`] supersedes it.
+    /// Negating `i16::MIN` saturates to `i16::MAX`.
+    pub fn set_axis_inversion(&mut self, x: bool, y: bool, z: bool) {
+        self.remap.negate = [x, y, z];
+    }
+
+    /// Set the coordinate frame of readings.
+    ///
+    /// The conversion is applied after the axis remapping (see [`Self::set_axis_remap`]), so
+    /// the remapping should align the sensor with the frame of the device (X forwards).
+    /// Defaults to [`Frame::Raw`].
+    pub fn set_output_frame(&mut self, frame: Frame) {
+        self.frame = frame;
+    }
+
+    /// Get the current output coordinate frame.
+    pub fn output_frame(&self) -> Frame {
+        self.frame
+    }
+
+    /// Override the interval, in microseconds, at which the status register is polled while
+    /// waiting for data (eg. in [`Self::read_all_when_ready`]).
+    ///
+    /// By default the status register is polled ten times per sample period of the configured
+    /// [`settings::OutputDataRate`].
+    /// A shorter interval reduces the latency between data becoming ready and it being read, at
+    /// the cost of more bus traffic and CPU wakeups; a longer one saves power but may cause
+    /// samples to be skipped if it approaches the sample period.
+    ///
+    /// Use [`Self::clear_poll_interval`] to return to the default.
+    pub fn set_poll_interval_us(&mut self, us: u32) {
+        self.poll_interval = Some(us);
+    }
+
+    /// Set how out-of-range readings are handled by [`Self::read_all`] and the methods built on
+    /// it.
+    ///
+    /// Defaults to [`OverflowPolicy::Raw`].
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow_policy = policy;
+    }
+
+    /// Get the current overflow policy.
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    /// Set how [`Self::is_ready_clocked`] decides whether new data is ready.
+    ///
+    /// Defaults to [`ReadyStrategy::StatusBit`].
+    pub fn set_ready_strategy(&mut self, strategy: ReadyStrategy) {
+        self.ready_strategy = strategy;
+        self.last_ready_tick = None;
+    }
+
+    /// Set the largest plausible change on any axis between consecutive readings, in counts.
+    ///
+    /// The device has no CRC, but a noisy bus tends to produce wild single-sample spikes.
+    /// With this set, a reading from [`Self::read_all`] or any other method which processes the
+    /// data as it does (such as [`Self::read_if_ready`], [`Self::read_all_with_status`] and
+    /// [`Self::read_all_with_temp`]) which differs from the previous one by more than `max_delta` on any axis is rejected and read
+    /// again: the data registers hold the same sample until the next conversion, so a re-read
+    /// gets an uncorrupted copy.
+    /// After [`Self::GLITCH_RETRIES`] failed retries, [`Error::Glitch`] is returned and the
+    /// previous reading is forgotten, so the next reading is accepted whatever its value.
+    ///
+    /// The threshold must be tuned to the application: too low, and real movement is reported
+    /// as glitches; too high, and glitches get through.
+    /// It should be at least the largest change expected in one sample period at the configured
+    /// [`settings::OutputDataRate`], plus a margin for noise.
+    pub fn set_max_delta(&mut self, max_delta: i16) {
+        self.max_delta = Some(max_delta);
+    }
+
+    /// Stop checking for implausible changes between readings.
+    ///
+    /// See [`Self::set_max_delta`].
+    pub fn clear_max_delta(&mut self) {
+        self.max_delta = None;
+        self.last_reading = None;
+    }
+
+    /// Set the order of the bytes in the device's 16-bit registers (the axis data and
+    /// temperature).
+    ///
+    /// The datasheet specifies [`ByteOrder::LittleEndian`], which is the default, but some
+    /// clones return their data byte-swapped.
+    /// If readings jump wildly or flip sign when the sensor is moved slowly, try
+    /// [`ByteOrder::BigEndian`].
+    pub fn set_byte_order(&mut self, order: ByteOrder) {
+        self.byte_order = order;
+    }
+
+    /// Choose whether data reads are made one register at a time.
+    ///
+    /// By default, [`Self::read_all`] reads all six data registers in a single burst, relying on
+    /// the device's pointer rollover (see [`Self::set_pointer_rollover`]).
+    /// If rollover might be disabled, eg. because the device is reconfigured by another bus user,
+    /// enable this to read each register in its own transaction instead.
+    /// This is robust to the rollover state, but takes six transactions per reading instead of
+    /// one, so increases bus load and latency considerably.
+    pub fn set_explicit_reads(&mut self, explicit: bool) {
+        self.explicit_reads = explicit;
+    }
+
+    /// Remove any override set with [`Self::set_poll_interval_us`], so the poll interval is
+    /// derived from the configured [`settings::OutputDataRate`] again.
+    pub fn clear_poll_interval(&mut self) {
+        self.poll_interval = None;
+    }
+
+    /// Get the temperature of the device, in raw counts.
+    ///
+    /// The gain of the sensor is factory calibrated to roughly 100 counts per °C, but its offset
+    /// isn't.
+    /// Note that the temperature is *not* expected to be absolutely accurate, but *is* expected to be
+    /// consistent with itself.
+    pub fn get_temp(&mut self) -> Result<i16, I::Error> {
+        self.read_reg16(registers::Register16::TOUT)
+    }
+
+    /// Get the mean of `n` temperature readings, in raw counts as in [`Self::get_temp`].
+    ///
+    /// This blocks for `n` reads back-to-back, without waiting on the data ready flag.
+    /// The device only updates the temperature alongside the axis data, so reads within one
+    /// sample period see the same value: the averaging only smooths out noise across as many
+    /// samples as the reads span.
+    /// An `n` of `0` is treated as `1`.
+    pub fn average_temperature(&mut self, n: u8) -> Result<i16, I::Error> {
+        let n = n.max(1);
+        let mut sum = 0i32;
+        for _ in 0..n {
+            sum += i32::from(self.get_temp()?);
+        }
+        // The mean of `i16`s always fits in an `i16`
+        #[allow(clippy::cast_possible_truncation)]
+        let mean = (sum / i32::from(n)) as i16;
+        Ok(mean)
+    }
+
+    /// Check, on a best-effort basis, whether the device has a working temperature sensor.
+    ///
+    /// Some clones don't implement the temperature registers, and return a constant value.
+    /// This reads the temperature 8 times, one sample period apart, and returns `true` if the
+    /// reading ever changes: the reading from a working sensor jitters by a few counts between
+    /// samples.
+    /// A false negative is possible if the reading happens to be perfectly stable, so treat a
+    /// `false` result as a hint rather than proof.
+    /// The device must not be on standby, since the temperature is only updated alongside
+    /// measurements.
+    pub fn has_temperature<D: DelayUs>(&mut self, delay: &mut D) -> Result<bool, I::Error> {
+        let first = self.get_temp()?;
+        for _ in 1..8 {
+            delay.delay_us(self.sample_period_us());
+            if self.get_temp()? != first {
+                return Ok(true);
+            }
+        }
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        warn!(
+            "Temperature stayed at {} counts: the sensor may be unsupported",
+            first
+        );
+        Ok(false)
+    }
+
+    /// Get the raw contents of the temperature registers, without any interpretation.
+    ///
+    /// The bytes are in register order: the LSB (`0x07`) followed by the MSB (`0x08`).
+    /// Prefer [`Self::get_temp`] unless you need to apply your own calibration curve.
+    pub fn get_temp_raw_bytes(&mut self) -> Result<[u8; 2], I::Error> {
+        self.read_reg16_raw(registers::Register16::TOUT)
+    }
+
+    /// Get the currently set [`Settings`] on the device.
+    ///
+    /// If the cache has been marked stale with [`Self::invalidate_cache`], the driver's cached
+    /// [`Settings`] and mode are updated to match.
+    /// See [`Self::control1_raw`] to get the register value this is decoded from.
     pub fn settings(&mut self) -> Result<Settings, I::Error> {
-        let val = self.read_raw(Settings::ADDR)?;
-        Ok(Settings::from(val))
+        let val = self.control1_raw()?;
+        let set = Settings::from(val);
+        if self.cache_stale {
+            self.settings = set;
+            self.mode = settings::Mode::from(val);
+            self.cache_stale = false;
+        }
+        Ok(set)
+    }
+
+    /// Mark the driver's cached [`Settings`] and mode as stale, without any bus access.
+    ///
+    /// Use this when the device may have been reconfigured behind the driver's back, but
+    /// re-reading it can wait: the cache is refreshed by the next call to [`Self::settings`].
+    /// Until then, the stale values are still used, eg. for unit conversions and
+    /// [`Self::mode`].
+    /// [`Self::sync`] refreshes the cache immediately instead.
+    pub fn invalidate_cache(&mut self) {
+        self.cache_stale = true;
+    }
+
+    /// Read the [`Settings`] and mode from the device, and log them in a human-readable form.
+    ///
+    /// This is intended for bring-up logs, eg. `10Hz, 64x oversampling, +/-2G, mode Continuous`,
+    /// and is logged at `info` level regardless of the `log` feature, since it's only called
+    /// on request.
+    #[cfg(feature = "defmt")]
+    pub fn log_settings(&mut self) -> Result<(), I::Error> {
+        let val = self.control1_raw()?;
+        let set = Settings::from(val);
+        defmt::info!(
+            "{=u32}Hz, {=u16}x oversampling, +/-{=f32}G, mode {}",
+            set.odr.hz(),
+            set.osr.ratio(),
+            set.rng.max_field_gauss(),
+            settings::Mode::from(val)
+        );
+        Ok(())
+    }
+
+    /// Read identifying information from the device, eg. for bug reports.
+    ///
+    /// This reads the chip ID register (`0x0D`), which should contain
+    /// [`DeviceInfo::EXPECTED_CHIP_ID`].
+    /// The QMC5883L has no documented revision register, so no revision information is
+    /// available.
+    pub fn device_info(&mut self) -> Result<DeviceInfo, I::Error> {
+        let chip_id = self.read_register(RegisterAddr::ChipId)?;
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        if chip_id != DeviceInfo::EXPECTED_CHIP_ID {
+            warn!("Unexpected chip ID {=u8:#x}", chip_id);
+        }
+        Ok(DeviceInfo { chip_id })
+    }
+
+    /// Read every register on the device into a [`Snapshot`].
+    ///
+    /// This takes two transactions: registers `0x00` to `0x06`, then `0x07` to `0x0D`.
+    /// Pointer rollover (see [`Control2::ROL_PNT`]) wraps the pointer back to `0x00` after the
+    /// status register, so the registers past it can't be read in the same span.
+    /// Reading the status register consumes the status register as any read of it does: [`Status::DRDY`]
+    /// and [`Status::DOR`] are cleared by the read, so are reported in the snapshot but won't be
+    /// seen by the next status check.
+    /// No cached state (such as the [`Settings`]) is updated.
+    pub fn snapshot(&mut self) -> Result<Snapshot, I::Error> {
+        let mut buf = [0; 14];
+        let (data, rest) = buf.split_at_mut(7);
+        self.bus_read(registers::Register16::X as u8, data)?;
+        self.bus_read(registers::Register16::TOUT as u8, rest)?;
+        let order = self.byte_order;
+        let word = |i: usize| order.decode(&buf[i..i + 2]);
+        Ok(Snapshot {
+            data: Measurement::from((word(0), word(2), word(4))),
+            status: Status::from_bits_truncate(buf[6]),
+            temperature: word(7),
+            settings: Settings::try_from_bits(buf[9]).ok(),
+            mode: settings::Mode::from(buf[9]),
+            control2: Control2::from_bits_truncate(buf[10]),
+            set_reset_period: buf[11],
+            chip_id: buf[13],
+        })
+    }
+
+    /// Get the raw value of the control register holding the [`Settings`] and mode (`0x09`).
+    ///
+    /// This is useful for debugging: prefer [`Self::settings`] and [`Self::read_mode`] for the
+    /// decoded values.
+    pub fn control1_raw(&mut self) -> Result<u8, I::Error> {
+        self.read_raw(Settings::ADDR)
+    }
+
+    /// Write the [`Settings`], the [`Control2`] flags and the SET/RESET period in a single bus
+    /// transaction.
+    ///
+    /// The three registers (`0x09` to `0x0B`) are contiguous, so can be written in one burst.
+    /// Compared to configuring them separately, this shortens the window in which the device is
+    /// only partly configured, and reduces bus traffic.
+    /// [`Control2::SOFT_RST`] is ignored if set, since it would undo the rest of the write: use
+    /// [`Self::reset`] first if needed.
+    /// As with [`Self::change_settings`], the device is taken off standby.
+    pub fn configure_all(
+        &mut self,
+        set: Settings,
+        control2: Control2,
+        reset_period: u8,
+    ) -> Result<(), Error<I::Error>> {
+        if !set.is_supported() {
+            return Err(Error::UnsupportedCombo);
+        }
+        let control2 = control2 - Control2::SOFT_RST;
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Applying {:?} to magnetometer with a single write", set);
+        self.bus_write(&[Settings::ADDR, set.into(), control2.bits(), reset_period])
+            .map_err(Error::I2c)?;
+        self.settings = set;
+        self.mode = settings::Mode::Continuous;
+        Ok(())
+    }
+
+    /// Read the raw contents of the three configuration registers, in a single transaction.
+    ///
+    /// The tuple holds, in order:
+    /// - control register 1 (`0x09`): the [`Settings`] and measurement mode
+    /// - control register 2 (`0x0A`): the [`Control2`] flags
+    /// - the SET/RESET period (`0x0B`)
+    ///
+    /// This is lower-level than [`Self::settings`]: it captures the device's configuration
+    /// exactly, including anything the driver doesn't model, so it can be restored with
+    /// [`Self::write_config`], eg. across a reset.
+    pub fn read_config(&mut self) -> Result<(u8, u8, u8), I::Error> {
+        let mut buf = [0; 3];
+        self.bus_read(Settings::ADDR, &mut buf)?;
+        Ok((buf[0], buf[1], buf[2]))
+    }
+
+    /// Write the raw contents of the three configuration registers, as returned by
+    /// [`Self::read_config`], in a single transaction.
+    ///
+    /// The driver's cached [`Settings`] and mode are updated from control register 1.
+    /// [`Control2::SOFT_RST`] is ignored if set, since it would undo the rest of the write.
+    /// Unlike [`Self::configure_all`], the values aren't checked, so take care restoring a
+    /// configuration which didn't come from the device.
+    pub fn write_config(&mut self, config: (u8, u8, u8)) -> Result<(), I::Error> {
+        let (control1, control2, reset_period) = config;
+        let control2 = control2 & !Control2::SOFT_RST.bits();
+        self.bus_write(&[Settings::ADDR, control1, control2, reset_period])?;
+        self.settings = Settings::from(control1);
+        self.mode = settings::Mode::from(control1);
+        Ok(())
+    }
+
+    /// Change the current [`Settings`] on the device, then read them back to check they were
+    /// applied.
+    ///
+    /// Returns [`Error::Verify`] if the value read back doesn't match, which can indicate a flaky
+    /// bus or the wrong device address.
+    pub fn change_settings_verified(&mut self, set: Settings) -> Result<(), Error<I::Error>> {
+        self.change_settings(set)?;
+        let written = u8::from(set);
+        let read = self.read_raw(Settings::ADDR).map_err(Error::I2c)?;
+        // Ignore the mode bits
+        if read & !Settings::MODE_MASK == written & !Settings::MODE_MASK {
+            Ok(())
+        } else {
+            #[cfg(all(feature = "defmt", feature = "log"))]
+            warn!(
+                "Wrote settings {=u8:#x}, but read back {=u8:#x}",
+                written, read
+            );
+            Err(Error::Verify)
+        }
+    }
+
+    /// Change the current [`Settings`] on the device.
+    ///
+    /// Returns [`Error::UnsupportedCombo`] without writing anything if the output data rate
+    /// can't be achieved with the oversampling ratio (see [`Settings::is_supported`]): use
+    /// [`Self::change_settings_clamped`] to lower the rate instead.
+    ///
+    /// Writing the settings takes the device off standby; in strict mode, this returns
+    /// [`Error::InStandby`] instead (see [`Self::set_strict`]).
+    pub fn change_settings(&mut self, set: Settings) -> Result<(), Error<I::Error>> {
+        if !set.is_supported() {
+            return Err(Error::UnsupportedCombo);
+        }
+        self.check_strict()?;
+        self.write_settings(set).map_err(Error::I2c)
+    }
+
+    /// Change the current [`Settings`] on the device, lowering the output data rate if the
+    /// oversampling ratio can't achieve it.
+    ///
+    /// Returns the settings actually applied: see [`Settings::clamped`].
+    /// As with [`Self::change_settings`], this returns [`Error::InStandby`] on standby in strict
+    /// mode.
+    pub fn change_settings_clamped(&mut self, set: Settings) -> Result<Settings, Error<I::Error>> {
+        self.check_strict()?;
+        let set = set.clamped();
+        self.write_settings(set).map_err(Error::I2c)?;
+        Ok(set)
+    }
+
+    /// Write the settings to the device, without checking them.
+    fn write_settings(&mut self, set: Settings) -> Result<(), I::Error> {
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        debug!("Applying {:?} to magnetometer", set);
+        self.write_raw(Settings::ADDR, set.into())?;
+        self.settings = set;
+        // Settings are always written with the continuous measurement bit set
+        self.mode = settings::Mode::Continuous;
+        Ok(())
+    }
+
+    /// Change the current [`Settings`] on the device, then wait for the new settings to take
+    /// effect.
+    ///
+    /// Straight after a change, the device may be part-way through a conversion with the old
+    /// settings, so the next sample can be stale or transitional.
+    /// This waits for [`Settings::latency_us`] of the new settings: one sample period plus the
+    /// conversion time, which grows with the [`settings::OverSampleRatio`].
+    /// Data read afterwards reflects the new settings.
+    pub fn change_settings_settled<D: DelayUs>(
+        &mut self,
+        set: Settings,
+        delay: &mut D,
+    ) -> Result<(), Error<I::Error>> {
+        self.change_settings(set)?;
+        delay.delay_us(set.latency_us());
+        Ok(())
+    }
+
+    /// Change the current [`Settings`] on the device, returning the driver for chaining.
+    ///
+    /// See [`Self::change_settings`].
+    ///
+    /// ```no_run
+    /// # fn main() {
+    /// # let mock_i2c = embedded_hal_mock::i2c::Mock::new(&[]);
+    /// use qmc5883l::{QMC8553L, settings::{FullScale, Settings}};
+    /// let custom = Settings {
+    ///     rng: FullScale::RNG8G,
+    ///     ..Settings::default()
+    /// };
+    /// let mag = QMC8553L::new(mock_i2c, Settings::default())
+    ///     .unwrap()
+    ///     .with_settings(custom)
+    ///     .unwrap()
+    ///     .with_declination(-1.5);
+    /// # }
+    /// ```
+    pub fn with_settings(mut self, set: Settings) -> Result<Self, Error<I::Error>> {
+        self.change_settings(set)?;
+        Ok(self)
+    }
+
+    /// Set the hard-iron offsets, returning the driver for chaining.
+    ///
+    /// See [`Self::set_hard_iron`].
+    #[must_use]
+    pub fn with_hard_iron(mut self, hard_iron: HardIron) -> Self {
+        self.set_hard_iron(hard_iron);
+        self
+    }
+
+    /// Set the magnetic declination, returning the driver for chaining.
+    ///
+    /// See [`Self::set_declination`].
+    #[must_use]
+    pub fn with_declination(mut self, declination: f32) -> Self {
+        self.set_declination(declination);
+        self
+    }
+}
+
+/// Prints the cached state of the driver, without performing any bus access.
+impl<I: I2c + fmt::Debug> fmt::Debug for QMC8553L<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dbg = f.debug_struct("QMC8553L");
+        dbg.field("i2c", &self.i2c)
+            .field("addr", &self.addr)
+            .field("mode", &self.mode)
+            .field("settings", &self.settings)
+            .field("cache_stale", &self.cache_stale)
+            .field("strict", &self.strict)
+            .field("hard_iron", &self.hard_iron)
+            .field("declination", &self.declination)
+            .field("scale", &self.scale)
+            .field("remap", &self.remap)
+            .field("frame", &self.frame)
+            .field("poll_interval", &self.poll_interval)
+            .field("explicit_reads", &self.explicit_reads)
+            .field("overflow_policy", &self.overflow_policy)
+            .field("ready_strategy", &self.ready_strategy)
+            .field("last_ready_tick", &self.last_ready_tick)
+            .field("last_status", &self.last_status)
+            .field("auto_calibrator", &self.auto_calibrator)
+            .field("zero", &self.zero)
+            .field("max_delta", &self.max_delta)
+            .field("last_reading", &self.last_reading)
+            .field("last_changed", &self.last_changed)
+            .field("saturation_counts", &self.saturation_counts)
+            .field("overflow_margin", &self.overflow_margin)
+            .field("samples_read", &self.samples_read)
+            .field("cached_temp", &self.cached_temp)
+            .field("temp_age", &self.temp_age)
+            .field("byte_order", &self.byte_order);
+        #[cfg(feature = "float")]
+        dbg.field("last_cardinal", &self.last_cardinal)
+            .field("cardinal_margin", &self.cardinal_margin)
+            .field("temp_coefficient", &self.temp_coefficient)
+            .field("reference_temp", &self.reference_temp)
+            .field("sensitivity", &self.sensitivity);
+        dbg.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_hal_mock::i2c::{Mock, Transaction};
+
+    const ADDR: u8 = 0x0D;
+
+    /// The transactions performed by [`QMC8553L::new`].
+    fn init_transactions(set: Settings) -> Vec<Transaction> {
+        vec![
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![0x0a, 0b1000_0000]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+            Transaction::write_read(ADDR, vec![0x0a], vec![0b0100_0000]),
+            Transaction::write(ADDR, vec![Settings::ADDR, set.into()]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![set.into()]),
+        ]
+    }
+
+    #[test]
+    fn pointer_rollover_preserves_control2() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x0a], vec![0b0100_0001]),
+            Transaction::write(ADDR, vec![0x0a, 0b0000_0001]),
+            Transaction::write_read(ADDR, vec![0x0a], vec![0b0000_0001]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0001]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.set_pointer_rollover(false).unwrap();
+        mag.set_pointer_rollover(true).unwrap();
+
+        i2c.done();
+    }
+
+    #[test]
+    fn standby_wake_cycle() {
+        let set = Settings::default();
+        let active: u8 = set.into();
+        let standby = active & 0b1111_1100;
+
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write(ADDR, vec![Settings::ADDR, standby]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active]),
+            Transaction::write(ADDR, vec![Settings::ADDR, standby]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert!(!mag.on_standby());
+        mag.to_standby().unwrap();
+        assert!(mag.on_standby());
+        mag.wake().unwrap();
+        assert!(!mag.on_standby());
+        mag.to_standby().unwrap();
+        assert!(mag.on_standby());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn wake_settled_discards_first_sample() {
+        let set = Settings::default();
+        let active: u8 = set.into();
+        let standby = active & 0b1111_1100;
+
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write(ADDR, vec![Settings::ADDR, standby]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x00]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0xff; 6]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x00]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        mag.to_standby().unwrap();
+        mag.wake_settled(&mut delay).unwrap();
+        assert!(!mag.on_standby());
+        assert_eq!(mag.read_all().unwrap(), Measurement::from([1, 2, 3]));
+        assert_eq!(mag.samples_read(), 0);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn set_continuous_mode_bits() {
+        let set = Settings {
+            rng: settings::FullScale::RNG8G,
+            ..Settings::default()
+        };
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write(
+                ADDR,
+                vec![Settings::ADDR, set.to_raw(settings::Mode::Standby)],
+            ),
+            Transaction::write(ADDR, vec![Settings::ADDR, u8::from(set) & !0b11 | 0b01]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.to_standby().unwrap();
+        mag.set_continuous().unwrap();
+        assert_eq!(mag.mode(), settings::Mode::Continuous);
+        assert!(!mag.on_standby());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn status_read_keeps_standby() {
+        let set = Settings::default();
+
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write(ADDR, vec![Settings::ADDR, u8::from(set) & 0b1111_1100]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0000_0000]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0000_0001]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.to_standby().unwrap();
+        assert!(!mag.is_ready().unwrap());
+        assert!(mag.on_standby());
+        assert!(mag.is_ready().unwrap());
+        assert!(mag.on_standby());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn custom_address() {
+        const CUSTOM: u8 = 0x2C;
+        let set = Settings::default();
+
+        let expectations = [
+            Transaction::write(CUSTOM, vec![0x0b, 0x01]),
+            Transaction::write(CUSTOM, vec![0x0a, 0b1000_0000]),
+            Transaction::write(CUSTOM, vec![0x0a, 0b0100_0000]),
+            Transaction::write_read(CUSTOM, vec![0x0a], vec![0b0100_0000]),
+            Transaction::write(CUSTOM, vec![Settings::ADDR, set.into()]),
+            Transaction::write_read(CUSTOM, vec![Settings::ADDR], vec![set.into()]),
+            Transaction::write_read(CUSTOM, vec![0x06], vec![0b0000_0001]),
+        ];
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new_with_address(i2c.clone(), set, CUSTOM).unwrap();
+        assert!(mag.is_ready().unwrap());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn averaged_discards_overflow() {
+        let set = Settings::default();
+
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0000_0001]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x02, 0x02, 0x04, 0x04, 0x06, 0x06]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0000_0011]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x7f, 0x7f, 0x7f, 0x7f, 0x7f, 0x7f]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0000_0001]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x04, 0x04, 0x06, 0x06, 0x08, 0x08]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let data = mag.read_averaged(3, &mut delay).unwrap();
+        assert_eq!(<[i16; 3]>::from(data), [0x0303, 0x0505, 0x0707]);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn builder_applies_calibration() {
+        let set = Settings::default();
+
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x01, 0x01, 0x02, 0x02, 0x03, 0x03],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::builder(i2c.clone())
+            .settings(set)
+            .hard_iron(HardIron { x: 1, y: 2, z: 3 })
+            .axis_remap(AxisRemap {
+                source: [Axis::Z, Axis::X, Axis::Y],
+                negate: [false, false, true],
+            })
+            .init()
+            .unwrap();
+        assert_eq!(
+            <[i16; 3]>::from(mag.read_all_calibrated().unwrap()),
+            [0x0303 - 1, 0x0101 - 2, -0x0202 - 3]
+        );
+
+        i2c.done();
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn read_gauss_uses_range() {
+        use settings::FullScale;
+
+        let set = Settings {
+            rng: FullScale::RNG8G,
+            ..Settings::default()
+        };
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write_read(ADDR, vec![0x02], vec![0x0b, 0x0b]));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        // 3000 counts per Gauss in the 8G range
+        let expected = f32::from(0x0b0b_i16) / 3000.0;
+        assert!((mag.read_gauss(Axis::Y).unwrap() - expected).abs() < f32::EPSILON);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn temperature_decode() {
+        let set = Settings::default();
+
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            // +25.00°C relative at 100 counts per °C
+            Transaction::write_read(ADDR, vec![0x07], vec![0xc4, 0x09]),
+            // -1.00°C relative
+            Transaction::write_read(ADDR, vec![0x07], vec![0x9c, 0xff]),
+            Transaction::write_read(ADDR, vec![0x07], vec![0x9c, 0xff]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(mag.get_temp().unwrap(), 2500);
+        assert_eq!(mag.get_temp().unwrap(), -100);
+        assert_eq!(mag.get_temp_raw_bytes().unwrap(), [0x9c, 0xff]);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn axis_decode() {
+        let set = Settings::default();
+
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x00], vec![0x34, 0x12, 0xff, 0xff, 0x00, 0x80]),
+            Transaction::write_read(ADDR, vec![0x04], vec![0x01, 0x00]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(
+            mag.read_all().unwrap(),
+            Measurement::from([0x1234, -1, i16::MIN])
+        );
+        assert_eq!(mag.read(Axis::Z).unwrap(), 1);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn verified_settings() {
+        use settings::*;
+
+        let set = Settings {
+            odr: OutputDataRate::OSR50,
+            osr: OverSampleRatio::OSR256,
+            rng: FullScale::RNG8G,
+        };
+        let val: u8 = set.into();
+
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write(ADDR, vec![Settings::ADDR, val]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![val]),
+            Transaction::write(ADDR, vec![Settings::ADDR, val]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![val & 0b1111_1100]),
+            Transaction::write(ADDR, vec![Settings::ADDR, val]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![0]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.change_settings_verified(set), Ok(()));
+        assert_eq!(mag.change_settings_verified(set), Ok(()));
+        assert_eq!(mag.change_settings_verified(set), Err(Error::Verify));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn set_pulse_restores_period() {
+        let set = Settings::default();
+        let active: u8 = set.into();
+        let standby = active & 0b1111_1100;
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            // A non-default period is forced to the shortest, then restored
+            Transaction::write_read(ADDR, vec![0x0b], vec![0x20]),
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![Settings::ADDR, standby]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active]),
+            Transaction::write(ADDR, vec![0x0b, 0x20]),
+            // The recommended period is left alone
+            Transaction::write_read(ADDR, vec![0x0b], vec![0x01]),
+            Transaction::write(ADDR, vec![Settings::ADDR, standby]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active]),
+            Transaction::write(ADDR, vec![Settings::ADDR, standby]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        mag.do_set(&mut delay).unwrap();
+        assert!(!mag.on_standby());
+        // RESET is the same pulse pair
+        mag.do_reset_coil(&mut delay).unwrap();
+        mag.to_standby().unwrap();
+        assert_eq!(mag.do_set(&mut delay), Err(Error::InStandby));
+        assert_eq!(mag.do_reset_coil(&mut delay), Err(Error::InStandby));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn sample_loop_counts_skips() {
+        let set = Settings::default();
+
+        let mut expectations = init_transactions(set);
+        for status in [0b0000_0001, 0b0000_0101, 0b0000_0001] {
+            expectations.extend([
+                Transaction::write_read(ADDR, vec![0x06], vec![status]),
+                Transaction::write_read(ADDR, vec![0x00], vec![0; 6]),
+            ]);
+        }
+        let mut i2c = Mock::new(&expectations);
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut flagged = 0;
+        let stats = mag
+            .sample_loop(&mut delay, 3, |_, skipped| flagged += usize::from(skipped))
+            .unwrap();
+        assert_eq!(
+            stats,
+            SampleStats {
+                read: 3,
+                skipped: 1
+            }
+        );
+        assert_eq!(flagged, 1);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn poll_interval_override() {
+        let set = Settings::default();
+        let mut i2c = Mock::new(&init_transactions(set));
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let default = mag.poll_interval_us();
+        assert_eq!(default, 100_000 / set.odr.hz());
+        mag.set_poll_interval_us(250);
+        assert_eq!(mag.poll_interval_us(), 250);
+        mag.clear_poll_interval();
+        assert_eq!(mag.poll_interval_us(), default);
+
+        i2c.done();
+    }
+
+    #[cfg(feature = "nb")]
+    #[test]
+    fn read_all_nb_would_block() {
+        let set = Settings::default();
+
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0000_0000]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0000_0001]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(mag.read_all_nb(), Err(nb::Error::WouldBlock));
+        assert_eq!(mag.read_all_nb(), Ok(Measurement::from([1, 2, 3])));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn stamped_read() {
+        struct Fixed;
+        impl Clock for Fixed {
+            fn now(&self) -> u64 {
+                1234
+            }
+        }
+
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(
+            mag.read_stamped(&Fixed).unwrap(),
+            (1234, Measurement::from([1, 2, 3]))
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_mode_keeps_tracked_state() {
+        use settings::Mode;
+
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![u8::from(set)]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![0b1000_0000]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![0b0000_0011]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(mag.read_mode().unwrap(), Mode::Continuous);
+        assert_eq!(mag.read_mode().unwrap(), Mode::Standby);
+        assert!(!mag.on_standby());
+        assert_eq!(mag.read_mode().unwrap(), Mode::Reserved);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn recover_reseeds_pointer() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x0a], vec![0b0000_0001]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0001]),
+            Transaction::write(ADDR, vec![0x00]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.recover().unwrap();
+
+        i2c.done();
+    }
+
+    #[test]
+    fn explicit_reads() {
+        let mut expectations = init_transactions(Settings::default());
+        for (reg, val) in (0x00..0x06).zip([0x01, 0x00, 0xfe, 0xff, 0x00, 0x01]) {
+            expectations.push(Transaction::write_read(ADDR, vec![reg], vec![val]));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.set_explicit_reads(true);
+        assert_eq!(mag.read_all().unwrap(), Measurement::from([1, -2, 256]));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn reset_restores_configuration() {
+        let set = Settings {
+            rng: settings::FullScale::RNG8G,
+            ..Settings::default()
+        };
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x0b], vec![0x01]),
+            Transaction::write(ADDR, vec![0x0a, 0b1000_0000]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![Settings::ADDR, set.into()]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let offsets = HardIron {
+            x: 10,
+            y: -20,
+            z: 30,
+        };
+        mag.set_hard_iron(offsets);
+        mag.reset_to_defaults().unwrap();
+        assert_eq!(mag.hard_iron(), offsets);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn factory_reset_clears_calibration() {
+        let set = Settings {
+            rng: settings::FullScale::RNG8G,
+            ..Settings::default()
+        };
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write(ADDR, vec![0x0a, 0b1000_0000]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+            Transaction::write(ADDR, vec![0x0a, 0b1000_0000]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![Settings::ADDR, Settings::default().into()]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let offsets = HardIron {
+            x: 10,
+            y: -20,
+            z: 30,
+        };
+        mag.set_hard_iron(offsets);
+        mag.set_axis_scale((1.5, 1.0, 0.5));
+        mag.set_declination(2.5);
+        mag.enable_auto_calibrate(true);
+
+        // A bare device reset keeps the calibration
+        mag.reset().unwrap();
+        assert_eq!(mag.hard_iron(), offsets);
+        assert_eq!(mag.axis_scale(), (1.5, 1.0, 0.5));
+
+        mag.factory_reset().unwrap();
+        assert_eq!(mag.hard_iron(), HardIron::default());
+        assert_eq!(mag.axis_scale(), (1.0, 1.0, 1.0));
+        assert!(mag.declination().abs() < f32::EPSILON);
+        assert!(mag.auto_calibrator.is_none());
+        assert_eq!(mag.settings, Settings::default());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_into_buffer() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let mut log = [[0; 3]; 2];
+        mag.read_into(&mut log[1]).unwrap();
+        assert_eq!(log, [[0; 3], [1, 2, 3]]);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_if_ready_single_transaction() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write_read(
+                ADDR,
+                vec![0x00],
+                vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0],
+            ),
+            Transaction::write_read(
+                ADDR,
+                vec![0x00],
+                vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 1],
+            ),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.read_if_ready().unwrap(), None);
+        assert_eq!(mag.samples_read(), 0);
+        assert_eq!(
+            mag.read_if_ready().unwrap(),
+            Some(Measurement::from([1, 2, 3]))
+        );
+        assert_eq!(mag.samples_read(), 1);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn raw_register_access() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x0d], vec![0xff]),
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write_read(ADDR, vec![0x0c], vec![0x01]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.read_register(RegisterAddr::ChipId).unwrap(), 0xff);
+        mag.write_register(RegisterAddr::SetResetPeriod, 0x01)
+            .unwrap();
+        assert_eq!(mag.read_register_raw(0x0c).unwrap(), 0x01);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn overflow_policies() {
+        // X = 24_001 is just out of range, Z = -24_000 just in range
+        let raw = vec![0xc1, 0x5d, 0x00, 0x00, 0x40, 0xa2];
+        let mut expectations = init_transactions(Settings::default());
+        for _ in 0..3 {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], raw.clone()));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.read_all(), Ok(Measurement::from([24_001, 0, -24_000])));
+        mag.set_overflow_policy(OverflowPolicy::Error);
+        assert_eq!(mag.read_all(), Err(Error::Overflow));
+        mag.set_overflow_policy(OverflowPolicy::Clamp);
+        assert_eq!(mag.read_all(), Ok(Measurement::from([24_000, 0, -24_000])));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn reset_polls_for_completion() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![0x0a, 0b1000_0000]),
+            Transaction::write_read(ADDR, vec![0x0b], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x0b], vec![0x00]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.reset_with_delay(&mut delay).unwrap();
+        i2c.done();
+
+        // Without a delay, initialisation polls for completion before retrying: a reset which
+        // swallowed the settings also swallowed the rollover write, so that's re-applied
+        let set = Settings::default();
+        let expectations = [
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![0x0a, 0b1000_0000]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+            Transaction::write_read(ADDR, vec![0x0a], vec![0b0000_0000]),
+            Transaction::write(ADDR, vec![Settings::ADDR, set.into()]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![0x00]),
+            Transaction::write_read(ADDR, vec![0x0b], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x0b], vec![0x00]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+            Transaction::write_read(ADDR, vec![0x0a], vec![0b0100_0000]),
+            Transaction::write(ADDR, vec![Settings::ADDR, set.into()]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![set.into()]),
+            // Burst reads are used, since rollover is enabled after all
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]),
+        ];
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(mag.read_all(), Ok(Measurement::from([1, 2, 3])));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn axis_inversion() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x01, 0x00, 0x00, 0x80, 0x03, 0x00],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.set_axis_inversion(false, true, true);
+        assert_eq!(
+            mag.read_all().unwrap(),
+            Measurement::from([1, i16::MAX, -3])
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn device_info() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x0d], vec![0xff]),
+            Transaction::write_read(ADDR, vec![0x0d], vec![0x80]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert!(mag.device_info().unwrap().is_expected());
+        assert!(!mag.device_info().unwrap().is_expected());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_with_status() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0b0000_0011],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(
+            mag.read_all_with_status().unwrap(),
+            (Measurement::from([1, 2, 3]), Status::DRDY | Status::OVL)
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_with_raw_status() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0b1001_0001],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        // Undocumented bits are kept
+        assert_eq!(
+            mag.read_all_with_raw_status().unwrap(),
+            (Measurement::from([1, 2, 3]), 0b1001_0001)
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn temperature_support() {
+        let mut expectations = init_transactions(Settings::default());
+        for lsb in [0x10, 0x10, 0x11] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x07], vec![lsb, 0x09]));
+        }
+        for _ in 0..8 {
+            expectations.push(Transaction::write_read(ADDR, vec![0x07], vec![0xff, 0xff]));
+        }
+        let mut i2c = Mock::new(&expectations);
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert!(mag.has_temperature(&mut delay).unwrap());
+        assert!(!mag.has_temperature(&mut delay).unwrap());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn init_without_reset() {
+        let set = Settings::default();
+        let mut i2c = Mock::new(&[Transaction::write(ADDR, vec![Settings::ADDR, set.into()])]);
+
+        let mag = QMC8553L::new_no_reset(i2c.clone(), set).unwrap();
+        assert!(!mag.on_standby());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn configure_all_single_write() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write(
+            ADDR,
+            vec![Settings::ADDR, set.into(), 0b0100_0000, 0x01],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.configure_all(set, Control2::ROL_PNT | Control2::SOFT_RST, 0x01)
+            .unwrap();
+
+        i2c.done();
+    }
+
+    #[test]
+    fn config_backup_restore() {
+        let set = Settings::default();
+        let saved = Settings {
+            odr: settings::OutputDataRate::OSR200,
+            ..set
+        }
+        .to_raw(settings::Mode::Standby);
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![saved, 0b1100_0001, 0x01]),
+            Transaction::write(ADDR, vec![Settings::ADDR, saved, 0b0100_0001, 0x01]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let config = mag.read_config().unwrap();
+        assert_eq!(config, (saved, 0b1100_0001, 0x01));
+        mag.write_config(config).unwrap();
+        assert_eq!(mag.settings.odr, settings::OutputDataRate::OSR200);
+        assert!(mag.on_standby());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn reset_sequence() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write(ADDR, vec![0x0a, 0b1000_0000]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.reset().unwrap();
+
+        i2c.done();
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        use settings::*;
+
+        let set = Settings {
+            odr: OutputDataRate::OSR200,
+            osr: OverSampleRatio::OSR64,
+            rng: FullScale::RNG8G,
+        };
+        // OSR64 = 0b11, RNG8G = 0b01, OSR200 = 0b11, continuous mode
+        let val = 0b1101_1101;
+        assert_eq!(u8::from(set), val);
+
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write(ADDR, vec![Settings::ADDR, val]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![val]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.change_settings(set).unwrap();
+        assert_eq!(mag.settings().unwrap(), set);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn big_endian_clone() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x00], vec![0x12, 0x34, 0xff, 0xfe, 0x00, 0x01]),
+            Transaction::write_read(ADDR, vec![0x07], vec![0x09, 0xc4]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.set_byte_order(ByteOrder::BigEndian);
+        assert_eq!(mag.read_all().unwrap(), Measurement::from([0x1234, -2, 1]));
+        assert_eq!(mag.get_temp().unwrap(), 2500);
+
+        i2c.done();
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn cardinal_hysteresis() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        // Headings of 20°, 25° then 30°
+        for data in [
+            [0xac, 0x03, 0x56, 0x01, 0x00, 0x00],
+            [0x8a, 0x03, 0xa7, 0x01, 0x00, 0x00],
+            [0x62, 0x03, 0xf4, 0x01, 0x00, 0x00],
+        ] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], data.to_vec()));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(mag.cardinal().unwrap(), Cardinal::N);
+        // Past the boundary at 22.5°, but within the margin
+        assert_eq!(mag.cardinal().unwrap(), Cardinal::N);
+        assert_eq!(mag.cardinal().unwrap(), Cardinal::NE);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn chained_configuration() {
+        let set = Settings::default();
+        let custom = Settings {
+            osr: settings::OverSampleRatio::OSR128,
+            ..Settings::default()
+        };
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write(ADDR, vec![0x09, custom.into()]));
+        let mut i2c = Mock::new(&expectations);
+
+        let hard_iron = HardIron { x: 1, y: 2, z: 3 };
+        let mag = QMC8553L::new(i2c.clone(), set)
+            .unwrap()
+            .with_settings(custom)
+            .unwrap()
+            .with_hard_iron(hard_iron)
+            .with_declination(2.5);
+        assert_eq!(mag.settings, custom);
+        assert_eq!(mag.hard_iron(), hard_iron);
+        assert!((mag.declination() - 2.5).abs() < f32::EPSILON);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn stability_variance() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        // X alternates between 10 and 20, the other axes are constant
+        for x in [10u8, 20, 10, 20, 10] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x06], vec![0x01]));
+            expectations.push(Transaction::write_read(
+                ADDR,
+                vec![0x00],
+                vec![x, 0x00, 0x05, 0x00, 0xfb, 0xff],
+            ));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        assert_eq!(mag.stability(4, &mut delay).unwrap(), 25);
+        assert_eq!(mag.stability(1, &mut delay).unwrap(), 0);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn apply_control2_single_write() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write(ADDR, vec![0x0a, 0x01]));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.apply_control2(Control2Config {
+            soft_reset: false,
+            rollover: false,
+            interrupt: false,
+        })
+        .unwrap();
+
+        i2c.done();
+    }
+
+    #[test]
+    fn ring_sampler_polls() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x00],
+        ));
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x04, 0x00, 0x05, 0x00, 0x06, 0x00, 0x01],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut ring = RingSampler::<4>::new();
+        assert!(!ring.poll(&mut mag).unwrap());
+        assert!(ring.poll(&mut mag).unwrap());
+        assert_eq!(ring.latest(), Some(Measurement::from([4, 5, 6])));
+        assert_eq!(ring.len(), 1);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_with_temp_addresses_temp() {
+        let set = Settings::default();
+        // A distinct marker byte for each data and temperature register
+        let data = vec![0x10, 0x11, 0x20, 0x21, 0x30, 0x31];
+        let temp = vec![0x70, 0x71];
+        let mut expectations = init_transactions(set);
+        for _ in 0..2 {
+            // Pointer rollover wraps after 0x06, so the temperature needs its own read
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], data.clone()));
+            expectations.push(Transaction::write_read(ADDR, vec![0x07], temp.clone()));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let (data, temp) = mag.read_all_with_temp().unwrap();
+        assert_eq!(data, Measurement::from([0x1110, 0x2120, 0x3130]));
+        assert_eq!(temp, 0x7170);
+
+        mag.set_byte_order(ByteOrder::BigEndian);
+        let (data, temp) = mag.read_all_with_temp().unwrap();
+        assert_eq!(data, Measurement::from([0x1011, 0x2021, 0x3031]));
+        assert_eq!(temp, 0x7071);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_data_raw_untouched() {
+        let set = Settings::default();
+        let bytes = vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write_read(ADDR, vec![0x00], bytes.clone()));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.set_byte_order(ByteOrder::BigEndian);
+        mag.set_output_frame(Frame::Ned);
+        assert_eq!(mag.read_data_raw().unwrap().to_vec(), bytes);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn auto_calibration() {
+        let set = Settings::default();
+        let readings: [[i16; 3]; 6] = [
+            [1100, 100, 50],
+            [-900, 100, 50],
+            [100, 1100, 50],
+            [100, -900, 50],
+            [100, 100, 1050],
+            [100, 100, -950],
+        ];
+        let mut expectations = init_transactions(set);
+        for m in readings {
+            let bytes: Vec<u8> = m.iter().flat_map(|v| v.to_le_bytes()).collect();
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], bytes));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.enable_auto_calibrate(true);
+        mag.read_all_calibrated().unwrap();
+        // Not enough coverage yet
+        assert_eq!(mag.current_calibration().hard_iron, HardIron::default());
+        for _ in 1..readings.len() {
+            mag.read_all_calibrated().unwrap();
+        }
+
+        let HardIron { x, y, z } = mag.current_calibration().hard_iron;
+        assert!((x - 100).abs() <= 10);
+        assert!((y - 100).abs() <= 10);
+        assert!((z - 50).abs() <= 10);
+
+        i2c.done();
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn read_batch_stops_when_full() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        for val in [1, 2] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x06], vec![0x01]));
+            expectations.push(Transaction::write_read(
+                ADDR,
+                vec![0x00],
+                vec![val, 0x00, val, 0x00, val, 0x00],
+            ));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        let mut out = heapless::Vec::<Measurement, 2>::new();
+        assert_eq!(mag.read_batch(&mut out, 5, &mut delay).unwrap(), 2);
+        assert_eq!(out.get(1), Some(&Measurement::from([2; 3])));
+        assert_eq!(mag.read_batch(&mut out, 5, &mut delay).unwrap(), 0);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn mode_state_machine() {
+        use settings::Mode;
+
+        let set = Settings::default();
+        let active: u8 = set.into();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write(ADDR, vec![Settings::ADDR, active & 0b1111_1100]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0; 6]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active]),
+            Transaction::write(ADDR, vec![0x0a, 0x80]),
+            Transaction::write(ADDR, vec![0x0a, 0x40]),
+            Transaction::write(ADDR, vec![Settings::ADDR, 0b0000_0011]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(mag.mode(), Mode::Continuous);
+        mag.to_standby().unwrap();
+        assert_eq!(mag.mode(), Mode::Standby);
+        // Reading data doesn't wake the device
+        mag.read_all().unwrap();
+        assert!(mag.on_standby());
+        mag.wake().unwrap();
+        assert_eq!(mag.mode(), Mode::Continuous);
+        mag.reset().unwrap();
+        assert!(mag.on_standby());
+        mag.write_register(RegisterAddr::Control1, 0b0000_0011)
+            .unwrap();
+        assert_eq!(mag.mode(), Mode::Reserved);
+        assert!(!mag.on_standby());
+        mag.change_settings(set).unwrap();
+        assert_eq!(mag.mode(), Mode::Continuous);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn relative_to_zero() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x00], vec![0x00, 0x80, 0x10, 0x00, 0x00, 0x00]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0xff, 0x7f, 0x18, 0x00, 0xfe, 0xff]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.zero().unwrap();
+        // X saturates rather than wrapping
+        assert_eq!(
+            mag.read_relative().unwrap(),
+            Measurement::from([i16::MAX, 8, -2])
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn max_delta_rejects_glitches() {
+        let set = Settings::default();
+        let reading = |x: i16| {
+            let [lo, hi] = x.to_le_bytes();
+            Transaction::write_read(ADDR, vec![0x00], vec![lo, hi, 0, 0, 0, 0])
+        };
+        let mut expectations = init_transactions(set);
+        expectations.extend([reading(0), reading(1000), reading(10)]);
+        expectations.extend((0..5).map(|_| reading(5000)));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.set_max_delta(100);
+        assert_eq!(mag.read_all().unwrap(), Measurement::from([0, 0, 0]));
+        // The spike is retried
+        assert_eq!(mag.read_all().unwrap(), Measurement::from([10, 0, 0]));
+        assert_eq!(mag.read_all(), Err(Error::Glitch));
+        // The jump is accepted after giving up
+        assert_eq!(mag.read_all().unwrap(), Measurement::from([5000, 0, 0]));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn max_delta_covers_combined_reads() {
+        let set = Settings::default();
+        let reading = |x: i16| {
+            let [lo, hi] = x.to_le_bytes();
+            Transaction::write_read(ADDR, vec![0x00], vec![lo, hi, 0, 0, 0, 0])
+        };
+        let with_status = |x: i16| {
+            let [lo, hi] = x.to_le_bytes();
+            Transaction::write_read(ADDR, vec![0x00], vec![lo, hi, 0, 0, 0, 0, 0x01])
+        };
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            reading(0),
+            with_status(1000),
+            reading(10),
+            with_status(-1000),
+            reading(20),
+            reading(1000),
+            Transaction::write_read(ADDR, vec![0x07], vec![0x00, 0x00]),
+            reading(30),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.set_max_delta(100);
+        assert_eq!(mag.read_all().unwrap(), Measurement::from([0, 0, 0]));
+        assert_eq!(
+            mag.read_if_ready().unwrap(),
+            Some(Measurement::from([10, 0, 0]))
+        );
+        assert_eq!(
+            mag.read_all_with_status().unwrap(),
+            (Measurement::from([20, 0, 0]), Status::DRDY)
+        );
+        assert_eq!(
+            mag.read_all_with_temp().unwrap(),
+            (Measurement::from([30, 0, 0]), 0)
+        );
+
+        i2c.done();
     }
 
-    /// Change the current [`Settings`] on the device.
-    pub fn change_settings(&mut self, set: Settings) -> Result<(), I::Error> {
-        #[cfg(feature = "defmt")]
-        debug!("Applying {:?} to magnetometer", set);
-        self.write_raw(Settings::ADDR, set.into())
+    #[cfg(feature = "float")]
+    #[test]
+    fn heading_facing_east_in_every_frame() {
+        // Level and chip-up, facing east: north is to the sensor's left (+Y), and the field
+        // dips downwards (-Z)
+        let raw = vec![0x00, 0x00, 0xe8, 0x03, 0x0c, 0xfe];
+        let mut expectations = init_transactions(Settings::default());
+        for _ in 0..3 {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], raw.clone()));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        for frame in [Frame::Raw, Frame::Ned, Frame::Enu] {
+            mag.set_output_frame(frame);
+            assert!((mag.heading().unwrap() - 90.0).abs() < 0.01);
+        }
+
+        i2c.done();
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn compass_bearing() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        // Facing east once the offsets are removed
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x64, 0x00, 0x4c, 0x04, 0x00, 0x00],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let hard_iron = HardIron {
+            x: 100,
+            y: 100,
+            z: 0,
+        };
+        let mut compass = Compass::new(mag, hard_iron, (1.0, 1.0, 1.0), 10.0);
+        assert!((compass.bearing_degrees().unwrap() - 100.0).abs() < 0.01);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn is_ready_is_pure_poll_on_standby() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write(
+            ADDR,
+            vec![Settings::ADDR, u8::from(set) & 0b1111_1100],
+        ));
+        // Only status reads, no writes which would wake the device
+        expectations.extend((0..5).map(|_| Transaction::write_read(ADDR, vec![0x06], vec![0x00])));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.to_standby().unwrap();
+        for _ in 0..5 {
+            assert!(!mag.is_ready().unwrap());
+            assert!(mag.on_standby());
+            assert_eq!(mag.mode(), settings::Mode::Standby);
+        }
+
+        i2c.done();
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn angle_to_reference() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x00], vec![0x00, 0x00, 0x00, 0x00, 0x10, 0x00]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x00; 6]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let reference = Measurement::from([0, 100, 0]);
+        assert!((mag.angle_to(reference).unwrap() - 90.0).abs() < 0.1);
+        assert_eq!(mag.angle_to(reference), Err(Error::ZeroField));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn change_settings_settled_writes_once() {
+        let set = Settings::default();
+        let fast = Settings {
+            odr: settings::OutputDataRate::OSR200,
+            osr: settings::OverSampleRatio::OSR64,
+            ..set
+        };
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write(ADDR, vec![Settings::ADDR, fast.into()]));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        mag.change_settings_settled(fast, &mut delay).unwrap();
+        assert_eq!(mag.settings, fast);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn set_reset_signed_access() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x0b], vec![0xff]),
+            Transaction::write_read(ADDR, vec![0x0b], vec![0xff]),
+            Transaction::write(ADDR, vec![0x0b, 0x80]),
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(mag.set_reset_value().unwrap(), -1);
+        assert_eq!(mag.set_reset_period().unwrap(), 0xff);
+        mag.set_set_reset_value(i8::MIN).unwrap();
+        mag.set_set_reset_period(0x01).unwrap();
+
+        i2c.done();
+    }
+
+    #[test]
+    fn low_power_read_cycle() {
+        let set = Settings::default();
+        let active: u8 = set.into();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write(ADDR, vec![Settings::ADDR, active & 0b1111_1100]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active]),
+            // Stale data is discarded
+            Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x09; 6]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active & 0b1111_1100]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.to_standby().unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        assert_eq!(
+            mag.read_once_low_power(&mut delay).unwrap(),
+            Measurement::from([1, 2, 3])
+        );
+        assert!(mag.on_standby());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn falls_back_without_rollover() {
+        let set = Settings::default();
+        let expectations = [
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![0x0a, 0b1000_0000]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+            // The clone ignores the rollover write
+            Transaction::write_read(ADDR, vec![0x0a], vec![0b0000_0000]),
+            Transaction::write(ADDR, vec![Settings::ADDR, set.into()]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![set.into()]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x01], vec![0x00]),
+            Transaction::write_read(ADDR, vec![0x02], vec![0x02]),
+            Transaction::write_read(ADDR, vec![0x03], vec![0x00]),
+            Transaction::write_read(ADDR, vec![0x04], vec![0x03]),
+            Transaction::write_read(ADDR, vec![0x05], vec![0x00]),
+        ];
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(mag.read_all().unwrap(), Measurement::from([1, 2, 3]));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_on_interrupt_checks_pin() {
+        use embedded_hal::digital::{ErrorKind, ErrorType};
+
+        struct TestPin(bool);
+        impl ErrorType for TestPin {
+            type Error = ErrorKind;
+        }
+        impl InputPin for TestPin {
+            fn is_high(&self) -> Result<bool, Self::Error> {
+                Ok(self.0)
+            }
+            fn is_low(&self) -> Result<bool, Self::Error> {
+                Ok(!self.0)
+            }
+        }
+
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        // No bus traffic while the pin is low
+        assert_eq!(mag.read_all_on_interrupt(&mut TestPin(false)), Ok(None));
+        assert_eq!(
+            mag.read_all_on_interrupt(&mut TestPin(true)),
+            Ok(Some(Measurement::from([1, 2, 3])))
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn on_data_ready_reads_on_pin() {
+        use core::cell::Cell;
+        use embedded_hal::digital::{ErrorKind, ErrorType};
+
+        // Pin levels to report in turn, then fail
+        struct TestPin<'a>(&'a [bool], Cell<usize>);
+        impl ErrorType for TestPin<'_> {
+            type Error = ErrorKind;
+        }
+        impl InputPin for TestPin<'_> {
+            fn is_high(&self) -> Result<bool, Self::Error> {
+                let i = self.1.replace(self.1.get() + 1);
+                self.0.get(i).copied().ok_or(ErrorKind::Other)
+            }
+            fn is_low(&self) -> Result<bool, Self::Error> {
+                self.is_high().map(|high| !high)
+            }
+        }
+
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x04, 0x00, 0x05, 0x00, 0x06, 0x00]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut pin = TestPin(&[false, false, true, false, true], Cell::new(0));
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        let mut seen = Vec::new();
+        assert_eq!(
+            mag.on_data_ready(&mut pin, &mut delay, |m| seen.push(m)),
+            Err(Error::Pin)
+        );
+        assert_eq!(
+            seen,
+            [Measurement::from([1, 2, 3]), Measurement::from([4, 5, 6])]
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_axes_spans() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x02], vec![0x02, 0x00, 0x03, 0x00]),
+            Transaction::write_read(ADDR, vec![0x04], vec![0x03, 0x00]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(mag.read_axes([Axis::Y, Axis::Z]).unwrap(), [2, 3]);
+        assert_eq!(mag.read_axes([Axis::Z, Axis::X]).unwrap(), [3, 1]);
+        assert_eq!(mag.read_axes([]).unwrap(), []);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_ordered_permutes_one_read() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.extend((0..2).map(|_| {
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00])
+        }));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(
+            mag.read_ordered([Axis::Z, Axis::Y, Axis::X]).unwrap(),
+            [3, 2, 1]
+        );
+        assert_eq!(
+            mag.read_ordered([Axis::Y, Axis::Y, Axis::X]).unwrap(),
+            [2, 2, 1]
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn write_allow_list() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.push(Transaction::write(ADDR, vec![0x0c, 0x01]));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(
+            mag.write_register(RegisterAddr::ChipId, 0x00),
+            Err(Error::Forbidden)
+        );
+        assert_eq!(mag.write_register_raw(0x06, 0x00), Err(Error::Forbidden));
+        assert_eq!(mag.write_register_raw(0x0c, 0x01), Err(Error::Forbidden));
+        mag.write_register_unchecked(0x0c, 0x01).unwrap();
+
+        i2c.done();
+    }
+
+    #[test]
+    fn bring_up_sequence() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x0d], vec![0xff]),
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![0x0a, 0b1000_0000]),
+            Transaction::write_read(ADDR, vec![0x0b], vec![0x00]),
+            Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+            Transaction::write_read(ADDR, vec![0x0a], vec![0b0100_0000]),
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![0x09, set.into()]),
+            Transaction::write_read(ADDR, vec![0x0d], vec![0x00]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        mag.bring_up(&mut delay).unwrap();
+        assert_eq!(mag.mode(), settings::Mode::Continuous);
+        assert_eq!(mag.bring_up(&mut delay), Err(Error::UnexpectedChipId));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn saturation_counts() {
+        let mut expectations = init_transactions(Settings::default());
+        for raw in [
+            vec![0xc1, 0x5d, 0x00, 0x00, 0x40, 0xa2],
+            vec![0xc1, 0x5d, 0x00, 0x00, 0x00, 0x80],
+            vec![0xc1, 0x5d, 0x00, 0x00, 0x00, 0x80],
+        ] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], raw));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.set_overflow_policy(OverflowPolicy::Clamp);
+        mag.read_all_checked().unwrap();
+        mag.read_all_checked().unwrap();
+        assert_eq!(mag.saturation_counts(), (2, 0, 1));
+        // Unchecked reads aren't counted
+        mag.read_all().unwrap();
+        assert_eq!(mag.saturation_counts(), (2, 0, 1));
+        mag.reset_counts();
+        assert_eq!(mag.saturation_counts(), (0, 0, 0));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn occasional_temp() {
+        let data = vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00];
+        let read_data = || Transaction::write_read(ADDR, vec![0x00], data.clone());
+        let read_temp = |temp: u8| Transaction::write_read(ADDR, vec![0x07], vec![temp, 0x00]);
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            read_data(),
+            read_temp(10),
+            read_data(),
+            read_data(),
+            read_data(),
+            read_temp(20),
+            read_data(),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let temps: Vec<i16> = (0..5)
+            .map(|_| mag.read_all_with_occasional_temp(3).unwrap().1)
+            .collect();
+        assert_eq!(temps, [10, 10, 10, 20, 20]);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn watchdog_detects_stall() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x06], vec![0x00]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+        ]);
+        expectations.extend((0..4).map(|_| Transaction::write_read(ADDR, vec![0x06], vec![0x00])));
+        let mut i2c = Mock::new(&expectations);
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.set_poll_interval_us(100);
+        mag.watchdog(&mut delay, 300).unwrap();
+        assert_eq!(mag.watchdog(&mut delay, 300), Err(Error::Stalled));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn unsigned_reads() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0xff, 0xff, 0x00, 0x80, 0x01, 0x00],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.read_all_unsigned().unwrap(), (0xffff, 0x8000, 0x0001));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn average_temperature() {
+        let mut expectations = init_transactions(Settings::default());
+        for temp in [100i16, 104, -2, 102] {
+            expectations.push(Transaction::write_read(
+                ADDR,
+                vec![0x07],
+                temp.to_le_bytes().to_vec(),
+            ));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.average_temperature(4).unwrap(), 76);
+
+        i2c.done();
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn read_in_units() {
+        // X = 12000 (1 Gauss in the 2G range), Y = -6000, Z = 0
+        let buf = vec![0xe0, 0x2e, 0x90, 0xe8, 0x00, 0x00];
+        let mut expectations = init_transactions(Settings::default());
+        for _ in 0..4 {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], buf.clone()));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        for (units, expected) in [
+            (Units::Raw, (12000.0, -6000.0)),
+            (Units::Gauss, (1.0, -0.5)),
+            (Units::MicroTesla, (100.0, -50.0)),
+            (Units::Milligauss, (1000.0, -500.0)),
+        ] {
+            let (x, y, z) = mag.read_all_in(units).unwrap();
+            assert!((x - expected.0).abs() < 1e-3);
+            assert!((y - expected.1).abs() < 1e-3);
+            assert!(z.abs() < f32::EPSILON);
+        }
+
+        i2c.done();
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn temperature_correction() {
+        // X = 1000, Y = -500, Z = 0, at 2000 counts (+10°C from the reference)
+        let mut expectations = init_transactions(Settings::default());
+        for _ in 0..2 {
+            expectations.extend([
+                Transaction::write_read(ADDR, vec![0x00], vec![0xe8, 0x03, 0x0c, 0xfe, 0x00, 0x00]),
+                Transaction::write_read(ADDR, vec![0x07], vec![0xd0, 0x07]),
+            ]);
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.set_reference_temp(1000);
+        assert_eq!(mag.read_all_corrected().unwrap(), (1000.0, -500.0, 0.0));
+        // 1000ppm/°C over 10°C is a 1% gain
+        mag.set_temp_coefficient(1000.0);
+        let (x, y, z) = mag.read_all_corrected().unwrap();
+        assert!((x - 1000.0 / 1.01).abs() < 0.01);
+        assert!((y + 500.0 / 1.01).abs() < 0.01);
+        assert!(z.abs() < f32::EPSILON);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn fixed_rate_ready() {
+        struct Ticks(core::cell::Cell<u64>);
+        impl Clock for Ticks {
+            fn now(&self) -> u64 {
+                self.0.get()
+            }
+        }
+
+        let set = Settings {
+            odr: settings::OutputDataRate::OSR10,
+            ..Settings::default()
+        };
+        let mut expectations = init_transactions(set);
+        expectations.push(Transaction::write_read(ADDR, vec![0x06], vec![0x01]));
+        let mut i2c = Mock::new(&expectations);
+        let clock = Ticks(core::cell::Cell::new(0));
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert!(mag.is_ready_clocked(&clock).unwrap());
+        // 10Hz with a millisecond clock: ready every 100 ticks, without bus access
+        mag.set_ready_strategy(ReadyStrategy::FixedRate {
+            ticks_per_second: 1000,
+        });
+        assert!(mag.is_ready_clocked(&clock).unwrap());
+        clock.0.set(99);
+        assert!(!mag.is_ready_clocked(&clock).unwrap());
+        clock.0.set(100);
+        assert!(mag.is_ready_clocked(&clock).unwrap());
+        assert!(!mag.is_ready_clocked(&clock).unwrap());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn status_events() {
+        let mut expectations = init_transactions(Settings::default());
+        for status in [0b0001, 0b0011, 0b0011, 0b0100, 0b0010] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x06], vec![status]));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let events: Vec<_> = (0..5)
+            .map(|_| {
+                let events = mag.poll_events().unwrap();
+                (
+                    events.data_ready,
+                    events.overflow_started,
+                    events.data_skipped,
+                )
+            })
+            .collect();
+        assert_eq!(
+            events,
+            [
+                (true, false, false),
+                (true, true, false),
+                (true, false, false),
+                (false, false, true),
+                (false, true, false),
+            ]
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn sync_from_device() {
+        let set = Settings {
+            rng: settings::FullScale::RNG8G,
+            ..Settings::default()
+        };
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x09], vec![set.to_raw(settings::Mode::Standby)]),
+            Transaction::write_read(ADDR, vec![0x0a], vec![0b0000_0000]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.sync().unwrap();
+        assert_eq!(mag.mode(), settings::Mode::Standby);
+        assert_eq!(mag.settings, set);
+        assert!(mag.explicit_reads);
+
+        i2c.done();
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn measured_rate() {
+        struct Ticking(core::cell::Cell<u64>);
+        impl Clock for Ticking {
+            // Advances by 20ms every time it is read
+            fn now(&self) -> u64 {
+                let now = self.0.get();
+                self.0.set(now + 20);
+                now
+            }
+        }
+
+        let data = vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00];
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x06], vec![0x00]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x00], data.clone()),
+        ]);
+        for _ in 0..4 {
+            expectations.extend([
+                Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+                Transaction::write_read(ADDR, vec![0x00], data.clone()),
+            ]);
+        }
+        let mut i2c = Mock::new(&expectations);
+        let clock = Ticking(core::cell::Cell::new(0));
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        // 4 samples over the 20ms between the two clock reads
+        let rate = mag.measure_rate(4, &clock, 1000).unwrap();
+        assert!((rate - 200.0).abs() < 0.01);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn orientation_hint() {
+        let mut expectations = init_transactions(Settings::default());
+        for raw in [
+            vec![0x10, 0x00, 0x20, 0x00, 0x00, 0xf0],
+            vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        ] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], raw));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.orientation_hint(), Ok(Axis::Z));
+        assert_eq!(mag.orientation_hint(), Err(Error::ZeroField));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn heading_sectors() {
+        let mut expectations = init_transactions(Settings::default());
+        for raw in [
+            // Just west of north
+            vec![0xe8, 0x03, 0xf6, 0xff, 0x00, 0x00],
+            // East
+            vec![0x00, 0x00, 0xe8, 0x03, 0x00, 0x00],
+            vec![0x00, 0x00, 0xe8, 0x03, 0x00, 0x00],
+        ] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], raw));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.heading_sectors(16).unwrap(), 0);
+        assert_eq!(mag.heading_sectors(16).unwrap(), 4);
+        assert_eq!(mag.heading_sectors(0).unwrap(), 0);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn reporting_skip() {
+        let mut expectations = init_transactions(Settings::default());
+        for status in [0b0101, 0b0001] {
+            expectations.push(Transaction::write_read(
+                ADDR,
+                vec![0x00],
+                vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, status],
+            ));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let data = Measurement::from([1, 2, 3]);
+        assert_eq!(mag.read_all_reporting_skip(), Ok((data, true)));
+        assert_eq!(mag.read_all_reporting_skip(), Ok((data, false)));
+
+        i2c.done();
+    }
+
+    #[test]
+    #[cfg(feature = "sensor-trait")]
+    fn magnetometer_reading_trait() {
+        fn read_generic<M: MagnetometerReading>(mag: &mut M) -> [f32; 3] {
+            mag.read_magnetic_field().ok().unwrap()
+        }
+
+        let mut expectations = init_transactions(Settings::default());
+        // 1.5G, -0.25G and 0G at 12000 counts per Gauss
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x50, 0x46, 0x48, 0xf4, 0x00, 0x00],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let field = read_generic(&mut mag);
+        for (val, expected) in field.into_iter().zip([1.5, -0.25, 0.0]) {
+            assert!((val - expected).abs() < f32::EPSILON);
+        }
+
+        i2c.done();
+    }
+
+    #[test]
+    fn overflow_is_sustained() {
+        let mut expectations = init_transactions(Settings::default());
+        // Overflow persists across several reads, including data reads, then clears
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0011]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0xff, 0x7f, 0, 0, 0, 0]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0010]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0011]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0001]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0b0000]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let overflow = |events: Events| {
+            (
+                events.overflow,
+                events.overflow_started,
+                events.overflow_ended,
+            )
+        };
+        assert_eq!(overflow(mag.poll_events().unwrap()), (true, true, false));
+        mag.read_all().unwrap();
+        assert_eq!(overflow(mag.poll_events().unwrap()), (true, false, false));
+        assert_eq!(overflow(mag.poll_events().unwrap()), (true, false, false));
+        assert_eq!(overflow(mag.poll_events().unwrap()), (false, false, true));
+        assert_eq!(overflow(mag.poll_events().unwrap()), (false, false, false));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn init_retries_lost_settings() {
+        let set = Settings {
+            rng: settings::FullScale::RNG8G,
+            ..Settings::default()
+        };
+        let mut expectations = init_transactions(set);
+        // The first write is lost to the reset, so wait for it to complete before retrying
+        expectations.splice(
+            5..5,
+            [
+                Transaction::write_read(ADDR, vec![Settings::ADDR], vec![0x00]),
+                Transaction::write_read(ADDR, vec![0x0b], vec![0x01])
+                    .with_error(embedded_hal_mock::MockError::Io(std::io::ErrorKind::Other)),
+                Transaction::write_read(ADDR, vec![0x0b], vec![0x01]),
+                Transaction::write_read(ADDR, vec![0x0b], vec![0x00]),
+                Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+                Transaction::write_read(ADDR, vec![0x0a], vec![0b0100_0000]),
+                Transaction::write(ADDR, vec![Settings::ADDR, set.into()]),
+            ],
+        );
+        let mut i2c = Mock::new(&expectations);
+
+        QMC8553L::new(i2c.clone(), set).unwrap();
+        i2c.done();
+
+        let mut expectations = init_transactions(set);
+        expectations.truncate(4);
+        for attempt in 0..=QMC8553L::<Mock>::INIT_RETRIES {
+            if attempt > 0 {
+                expectations.extend([
+                    Transaction::write_read(ADDR, vec![0x0b], vec![0x00]),
+                    Transaction::write(ADDR, vec![0x0a, 0b0100_0000]),
+                    Transaction::write_read(ADDR, vec![0x0a], vec![0b0100_0000]),
+                ]);
+            }
+            expectations.extend([
+                Transaction::write(ADDR, vec![Settings::ADDR, set.into()]),
+                Transaction::write_read(ADDR, vec![Settings::ADDR], vec![0x00]),
+            ]);
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        assert!(matches!(
+            QMC8553L::new(i2c.clone(), set),
+            Err(Error::Config)
+        ));
+        i2c.done();
+    }
+
+    #[test]
+    fn new_in_standby() {
+        let set = Settings::default();
+        let standby = set.to_raw(settings::Mode::Standby);
+        let mut expectations = init_transactions(set);
+        expectations[4] = Transaction::write(ADDR, vec![Settings::ADDR, standby]);
+        expectations[5] = Transaction::write_read(ADDR, vec![Settings::ADDR], vec![standby]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mag = QMC8553L::new_standby(i2c.clone(), set).unwrap();
+        assert!(mag.on_standby());
+        assert_eq!(mag.settings, set);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn rejects_unsupported_combo() {
+        let set = Settings {
+            odr: settings::OutputDataRate::OSR200,
+            osr: settings::OverSampleRatio::OSR512,
+            ..Settings::default()
+        };
+        let clamped = Settings {
+            odr: settings::OutputDataRate::OSR50,
+            ..set
+        };
+        let mut expectations = init_transactions(Settings::default());
+        expectations.push(Transaction::write(ADDR, vec![0x09, clamped.into()]));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.change_settings(set), Err(Error::UnsupportedCombo));
+        assert_eq!(mag.change_settings_clamped(set), Ok(clamped));
+        assert_eq!(mag.settings, clamped);
+        i2c.done();
+
+        let mut i2c = Mock::new(&[]);
+        assert!(matches!(
+            QMC8553L::new(i2c.clone(), set),
+            Err(Error::UnsupportedCombo)
+        ));
+        i2c.done();
+    }
+
+    #[test]
+    fn near_overflow_margin() {
+        // 21600 is exactly 90% of the 2G range's 24000 counts
+        let mut expectations = init_transactions(Settings::default());
+        for raw in [
+            vec![0x60, 0x54, 0x00, 0x00, 0x00, 0x00],
+            vec![0x00, 0x00, 0x9f, 0xab, 0x00, 0x00],
+            vec![0x00, 0x00, 0x9f, 0xab, 0x00, 0x00],
+        ] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], raw));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        mag.set_overflow_margin_permille(900);
+        assert_eq!(
+            mag.read_all_checked().unwrap(),
+            Measurement::from([21600, 0, 0])
+        );
+        assert_eq!(mag.read_all_checked(), Err(Error::NearOverflow));
+        mag.clear_overflow_margin();
+        assert_eq!(
+            mag.read_all_checked().unwrap(),
+            Measurement::from([0, -21601, 0])
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn register_snapshot() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(
+                ADDR,
+                vec![0x00],
+                vec![0x01, 0x00, 0xfe, 0xff, 0x03, 0x00, 0x05],
+            ),
+            Transaction::write_read(
+                ADDR,
+                vec![0x07],
+                vec![0x10, 0x27, set.into(), 0x40, 0x01, 0x00, 0xff],
+            ),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert_eq!(
+            mag.snapshot().unwrap(),
+            Snapshot {
+                data: Measurement::from([1, -2, 3]),
+                status: Status::DRDY | Status::DOR,
+                temperature: 10_000,
+                settings: Some(set),
+                mode: settings::Mode::Continuous,
+                control2: Control2::ROL_PNT,
+                set_reset_period: 0x01,
+                chip_id: 0xff,
+            }
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn custom_sensitivity() {
+        // X = 12000, Y = -6000, Z = 0
+        let buf = vec![0xe0, 0x2e, 0x90, 0xe8, 0x00, 0x00];
+        let mut expectations = init_transactions(Settings::default());
+        for _ in 0..2 {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], buf.clone()));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let rng = Settings::default().rng;
+        assert!((mag.sensitivity(rng) - rng.scale_factor()).abs() < f32::EPSILON);
+        mag.set_sensitivity(rng, 10_000.0);
+        let (x, y, z) = mag.read_all_gauss().unwrap();
+        assert!((x - 1.2).abs() < 1e-6);
+        assert!((y + 0.6).abs() < 1e-6);
+        assert!(z.abs() < f32::EPSILON);
+        let (x, _, _) = mag.read_all_in(Units::MicroTesla).unwrap();
+        assert!((x - 120.0).abs() < 1e-3);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn settled_needs_stable_temperature() {
+        let set = Settings::default();
+        let mut expectations = init_transactions(set);
+        for end_temp in [1_010i16, 1_100] {
+            expectations.push(Transaction::write_read(
+                ADDR,
+                vec![0x07],
+                1_000i16.to_le_bytes().to_vec(),
+            ));
+            for _ in 0..QMC8553L::<Mock>::SETTLE_SAMPLES {
+                expectations.push(Transaction::write_read(ADDR, vec![0x06], vec![0x01]));
+                expectations.push(Transaction::write_read(
+                    ADDR,
+                    vec![0x00],
+                    vec![0x10, 0x00, 0x05, 0x00, 0xfb, 0xff],
+                ));
+            }
+            expectations.push(Transaction::write_read(
+                ADDR,
+                vec![0x07],
+                end_temp.to_le_bytes().to_vec(),
+            ));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        assert!(mag.is_settled(&mut delay).unwrap());
+        assert!(!mag.is_settled(&mut delay).unwrap());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_xy_only() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.push(Transaction::write_read(
+            ADDR,
+            vec![0x00],
+            vec![0x34, 0x12, 0xfe, 0xff],
+        ));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(mag.read_xy().unwrap(), (0x1234, -2));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn guided_calibration() {
+        let mut expectations = init_transactions(Settings::default());
+        for raw in [
+            vec![0x64, 0x00, 0xc8, 0x00, 0x64, 0x00],
+            vec![0x9c, 0xff, 0x00, 0x00, 0x9c, 0xff],
+        ] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x06], vec![0x01]));
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], raw));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        let mut reported = Vec::new();
+        let cal = mag
+            .calibrate(2, &mut delay, |percent| reported.push(percent))
+            .unwrap();
+        assert_eq!(reported, [12, 25]);
+        assert_eq!(cal.hard_iron, HardIron { x: 0, y: 100, z: 0 });
+        // Not applied until the caller chooses to
+        assert_eq!(mag.hard_iron(), HardIron::default());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn status_flags() {
+        let mut expectations = init_transactions(Settings::default());
+        expectations.push(Transaction::write_read(ADDR, vec![0x06], vec![0b011]));
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let status = mag.status().unwrap();
+        assert!(status.contains(Status::DRDY | Status::OVL));
+        assert!(!status.contains(Status::DOR));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn read_only_changes() {
+        let mut expectations = init_transactions(Settings::default());
+        for raw in [
+            vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 1],
+            vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0],
+            vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 1],
+            vec![0x01, 0x00, 0x02, 0x00, 0x04, 0x00, 1],
+        ] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], raw));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert_eq!(
+            mag.read_if_changed().unwrap(),
+            Some(Measurement::from([1, 2, 3]))
+        );
+        // Not ready, then ready but unchanged
+        assert_eq!(mag.read_if_changed().unwrap(), None);
+        assert_eq!(mag.read_if_changed().unwrap(), None);
+        assert_eq!(
+            mag.read_if_changed().unwrap(),
+            Some(Measurement::from([1, 2, 4]))
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn rollover_verification() {
+        let data = vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00];
+        let mut with_status = data.clone();
+        with_status.push(0x04);
+        let mut expectations = init_transactions(Settings::default());
+        expectations.extend([
+            // A genuine part wraps back to the X register after the status register
+            Transaction::write_read(ADDR, vec![0x00], with_status.clone()),
+            Transaction::read(ADDR, data),
+            Transaction::write_read(ADDR, vec![0x00], with_status),
+            // Carried on through the temperature and control registers
+            Transaction::read(ADDR, vec![0x10, 0x27, 0x1d, 0x40, 0x00, 0x00]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        assert!(mag.verify_rollover().unwrap());
+        assert!(!mag.verify_rollover().unwrap());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn warm_boot_detection() {
+        let set = Settings::default();
+        let expectations = [
+            // Attaching to a configured device
+            Transaction::write_read(ADDR, vec![0x09], vec![u8::from(set)]),
+            Transaction::write_read(ADDR, vec![0x0a], vec![0x40]),
+            Transaction::write_read(ADDR, vec![0x09], vec![u8::from(set)]),
+            Transaction::write_read(ADDR, vec![0x0a], vec![0x40]),
+            // After a power-on
+            Transaction::write_read(ADDR, vec![0x09], vec![0x00]),
+            // Measuring, but rollover disabled
+            Transaction::write_read(ADDR, vec![0x09], vec![u8::from(set)]),
+            Transaction::write_read(ADDR, vec![0x0a], vec![0x00]),
+        ];
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::attach(i2c.clone()).unwrap();
+        assert_eq!(mag.settings, set);
+        assert!(mag.detect_warm_boot().unwrap());
+        assert!(!mag.detect_warm_boot().unwrap());
+        assert!(!mag.detect_warm_boot().unwrap());
+
+        i2c.done();
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn spherical_reading() {
+        // X = 1200, Y = 0, Z = -1200 (pointing down), then X = 0, Y = 1200, Z = 0
+        let mut expectations = init_transactions(Settings::default());
+        for raw in [
+            vec![0xb0, 0x04, 0x00, 0x00, 0x50, 0xfb],
+            vec![0x00, 0x00, 0xb0, 0x04, 0x00, 0x00],
+        ] {
+            expectations.push(Transaction::write_read(ADDR, vec![0x00], raw));
+        }
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), Settings::default()).unwrap();
+        let (magnitude, azimuth, inclination) = mag.read_spherical().unwrap();
+        assert!((magnitude - 0.1 * core::f32::consts::SQRT_2).abs() < 1e-4);
+        assert!(azimuth.abs() < 0.01);
+        assert!((inclination - 45.0).abs() < 0.01);
+
+        let (magnitude, azimuth, inclination) = mag.read_spherical().unwrap();
+        assert!((magnitude - 0.1).abs() < 1e-4);
+        assert!((azimuth - 90.0).abs() < 0.01);
+        assert!(inclination.abs() < 0.01);
+
+        i2c.done();
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn read_at_precision_picks_osr() {
+        let set = Settings {
+            odr: settings::OutputDataRate::OSR200,
+            osr: settings::OverSampleRatio::OSR64,
+            ..Settings::default()
+        };
+        // 256x oversampling is the least meeting 3mG, and can't keep up with 200Hz
+        let precise = Settings {
+            odr: settings::OutputDataRate::OSR100,
+            osr: settings::OverSampleRatio::OSR256,
+            ..set
+        };
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write(ADDR, vec![Settings::ADDR, precise.into()]),
+            Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]),
+            // Already precise enough, so no write
+            Transaction::write_read(ADDR, vec![0x06], vec![0x01]),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x04, 0x00, 0x05, 0x00, 0x06, 0x00]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        assert_eq!(
+            mag.read_all_at_precision(0.003, &mut delay),
+            Ok(Measurement::from([1, 2, 3]))
+        );
+        assert_eq!(mag.settings, precise);
+        assert_eq!(
+            mag.read_all_at_precision(0.003, &mut delay),
+            Ok(Measurement::from([4, 5, 6]))
+        );
+        assert_eq!(
+            mag.read_all_at_precision(0.001, &mut delay),
+            Err(Error::Unattainable)
+        );
+
+        i2c.done();
+    }
+
+    #[test]
+    fn invalidated_cache_refreshed_lazily() {
+        let set = Settings::default();
+        let other = Settings {
+            rng: settings::FullScale::RNG8G,
+            ..set
+        };
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![other.into()]),
+            Transaction::write_read(ADDR, vec![Settings::ADDR], vec![other.into()]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        // Reading without invalidating leaves the cache alone
+        assert_eq!(mag.settings(), Ok(other));
+        assert_eq!(mag.settings, set);
+
+        // Invalidating alone doesn't touch the bus
+        mag.invalidate_cache();
+        assert_eq!(mag.settings, set);
+        assert_eq!(mag.settings(), Ok(other));
+        assert_eq!(mag.settings, other);
+        assert!(!mag.cache_stale);
+
+        i2c.done();
+    }
+
+    #[test]
+    fn strict_mode_on_standby() {
+        let set = Settings::default();
+        let active: u8 = set.into();
+        let standby = active & 0b1111_1100;
+        let data =
+            || Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00]);
+
+        let mut expectations = init_transactions(set);
+        expectations.extend([
+            Transaction::write(ADDR, vec![Settings::ADDR, standby]),
+            // Lenient: stale data is read, and changing settings wakes the device
+            data(),
+            Transaction::write_read(ADDR, vec![0x04], vec![0x03, 0x00]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active]),
+            // Strict: only an explicit wake
+            Transaction::write(ADDR, vec![Settings::ADDR, standby]),
+            Transaction::write(ADDR, vec![Settings::ADDR, active]),
+            data(),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        assert!(!mag.is_strict());
+        mag.to_standby().unwrap();
+        assert_eq!(mag.read_all(), Ok(Measurement::from([1, 2, 3])));
+        assert_eq!(mag.read(Axis::Z), Ok(3));
+        mag.change_settings(set).unwrap();
+        assert!(!mag.on_standby());
+
+        mag.set_strict(true);
+        mag.to_standby().unwrap();
+        assert_eq!(mag.read_all(), Err(Error::InStandby));
+        assert_eq!(mag.read(Axis::Z), Err(Error::InStandby));
+        assert_eq!(mag.change_settings(set), Err(Error::InStandby));
+        assert_eq!(mag.change_settings_clamped(set), Err(Error::InStandby));
+        assert_eq!(mag.read_if_ready(), Err(Error::InStandby));
+        assert_eq!(mag.read_all_with_status(), Err(Error::InStandby));
+        assert_eq!(mag.read_all_with_raw_status(), Err(Error::InStandby));
+        assert_eq!(mag.read_all_with_temp(), Err(Error::InStandby));
+        assert!(mag.on_standby());
+        mag.wake().unwrap();
+        assert_eq!(mag.read_all(), Ok(Measurement::from([1, 2, 3])));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn self_test_restores_period() {
+        let set = Settings::default();
+        let ready = || Transaction::write_read(ADDR, vec![0x06], vec![0x01]);
+        let data = |x: u8| {
+            Transaction::write_read(ADDR, vec![0x00], vec![x, 0x00, 0x02, 0x00, 0x03, 0x00])
+        };
+        let check = |after: u8| {
+            vec![
+                Transaction::write_read(ADDR, vec![0x0b], vec![0x01]),
+                ready(),
+                data(0x01),
+                Transaction::write(ADDR, vec![0x0b, 0x00]),
+                ready(),
+                data(0x01),
+                ready(),
+                data(0x01),
+                Transaction::write(ADDR, vec![0x0b, 0x01]),
+                ready(),
+                data(0x01),
+                ready(),
+                data(0x01),
+                ready(),
+                data(after),
+            ]
+        };
+        let mut expectations = init_transactions(set);
+        expectations.extend(check(0x01));
+        expectations.extend(check(0xf0));
+        // A failed read while SET/RESET is disabled still restores it
+        expectations.extend([
+            Transaction::write_read(ADDR, vec![0x0b], vec![0x01]),
+            ready(),
+            data(0x01),
+            Transaction::write(ADDR, vec![0x0b, 0x00]),
+            ready(),
+            Transaction::write_read(ADDR, vec![0x00], vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x00])
+                .with_error(embedded_hal_mock::MockError::Io(std::io::ErrorKind::Other)),
+            Transaction::write(ADDR, vec![0x0b, 0x01]),
+            Transaction::write(ADDR, vec![Settings::ADDR, u8::from(set) & 0b1111_1100]),
+        ]);
+        let mut i2c = Mock::new(&expectations);
+
+        let mut mag = QMC8553L::new(i2c.clone(), set).unwrap();
+        let mut delay = embedded_hal_mock::delay::MockNoop::new();
+        // Passes, then fails: X moved by 239 counts
+        assert_eq!(mag.self_test(&mut delay), Ok(true));
+        assert_eq!(mag.self_test(&mut delay), Ok(false));
+        assert!(mag.self_test(&mut delay).is_err());
+        mag.to_standby().unwrap();
+        assert_eq!(mag.self_test(&mut delay), Err(Error::InStandby));
+
+        i2c.done();
     }
 }
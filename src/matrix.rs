@@ -0,0 +1,65 @@
+use core::ops::Mul;
+
+use crate::Measurement;
+
+/// A 3×3 matrix, for applying linear corrections to [`Measurement`]s.
+///
+/// Multiplying a [`Measurement`] by a matrix gives the corrected field as `(x, y, z)`, eg. for
+/// soft-iron correction:
+///
+/// ```
+/// use qmc5883l::{Matrix3, Measurement};
+/// let correction = Matrix3::from_diagonal(1.0, 2.0, 0.5);
+/// let corrected = correction * Measurement::from([100, 100, 100]);
+/// assert_eq!(corrected, (100.0, 200.0, 50.0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Matrix3(pub [[f32; 3]; 3]);
+
+impl Matrix3 {
+    /// The identity matrix, which leaves measurements unchanged.
+    pub const IDENTITY: Self = Self::from_diagonal(1.0, 1.0, 1.0);
+
+    /// Create a matrix which scales each axis independently.
+    #[must_use]
+    pub const fn from_diagonal(x: f32, y: f32, z: f32) -> Self {
+        Self([[x, 0.0, 0.0], [0.0, y, 0.0], [0.0, 0.0, z]])
+    }
+}
+
+impl Default for Matrix3 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Mul<Measurement> for Matrix3 {
+    type Output = (f32, f32, f32);
+
+    fn mul(self, rhs: Measurement) -> Self::Output {
+        let v = [f32::from(rhs.x), f32::from(rhs.y), f32::from(rhs.z)];
+        let [x, y, z] = self
+            .0
+            .map(|row| row.iter().zip(v).map(|(a, b)| a * b).sum());
+        (x, y, z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity() {
+        let m = Measurement::from([120, -45, 8]);
+        assert_eq!(Matrix3::IDENTITY * m, (120.0, -45.0, 8.0));
+    }
+
+    #[test]
+    fn known_scaling() {
+        let matrix = Matrix3([[2.0, 0.0, 0.0], [0.0, 0.5, 1.0], [1.0, 1.0, 1.0]]);
+        let m = Measurement::from([10, 20, 30]);
+        assert_eq!(matrix * m, (20.0, 40.0, 60.0));
+    }
+}
@@ -0,0 +1,692 @@
+use core::fmt;
+use core::ops::{Add, Div, Mul, Sub};
+#[cfg(feature = "defmt")]
+use defmt::Format;
+#[cfg(feature = "float")]
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+use crate::{settings::FullScale, Axis};
+
+/// A single reading of all three axes of the sensor, in raw counts.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+#[allow(missing_docs)]
+pub struct Measurement {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+}
+
+impl Measurement {
+    /// The longest record written by [`Self::to_csv_bytes`], in bytes: three values of
+    /// `-32768`, two commas and a newline.
+    pub const CSV_MAX_LEN: usize = 21;
+
+    /// Get the value of a particular axis.
+    #[must_use]
+    pub fn get(&self, axis: Axis) -> i16 {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+
+    /// Check whether any axis is pinned at the extremes of `i16`, indicating saturation.
+    ///
+    /// This is a cheap check which needs no extra bus access, but complements rather than
+    /// replaces the device's overflow flag, which is set when the field is out of range even
+    /// if the reading isn't pinned.
+    #[must_use]
+    pub fn is_saturated(&self) -> bool {
+        self.into_iter()
+            .any(|(_, val)| val == i16::MIN || val == i16::MAX)
+    }
+
+    /// The squared magnitude of the field vector, in counts².
+    ///
+    /// This can't overflow, even with every axis at `i16::MIN`.
+    #[must_use]
+    pub fn magnitude_squared(&self) -> u32 {
+        self.into_iter()
+            .map(|(_, val)| u32::from(val.unsigned_abs()).pow(2))
+            .sum()
+    }
+
+    /// The magnitude of the field vector, in counts.
+    #[cfg(feature = "float")]
+    #[must_use]
+    pub fn magnitude(&self) -> f32 {
+        // Rounding to an `f32` loses far less than the sensor's noise
+        #[allow(clippy::cast_precision_loss)]
+        let squared = self.magnitude_squared() as f32;
+        squared.sqrt()
+    }
+
+    /// Convert the reading to milliGauss, using the sensitivity of the given range.
+    ///
+    /// Only integer arithmetic is used, rounding towards zero.
+    #[must_use]
+    pub fn to_milligauss(&self, rng: FullScale) -> (i32, i32, i32) {
+        let scale = i32::from(rng.counts_per_gauss());
+        // An `i16` multiplied by 1000 always fits in an `i32`
+        let convert = |val: i16| i32::from(val) * 1000 / scale;
+        (convert(self.x), convert(self.y), convert(self.z))
+    }
+
+    /// Convert the reading to milliGauss as in [`Self::to_milligauss`], rounding to the nearest
+    /// milliGauss.
+    ///
+    /// Truncating towards zero biases every reading towards zero, which accumulates when many
+    /// readings are summed or averaged.
+    /// Halves are rounded away from zero, so positive and negative readings are treated
+    /// symmetrically.
+    #[must_use]
+    pub fn to_milligauss_rounded(&self, rng: FullScale) -> (i32, i32, i32) {
+        let scale = i32::from(rng.counts_per_gauss());
+        // An `i16` multiplied by 2000 always fits in an `i32`
+        let convert = |val: i16| {
+            let twice = i32::from(val) * 2000;
+            (twice + twice.signum() * scale) / (2 * scale)
+        };
+        (convert(self.x), convert(self.y), convert(self.z))
+    }
+
+    /// Convert the reading to Q15 fixed point, normalised to the full scale of the given range.
+    ///
+    /// The rated full-scale reading (see [`FullScale::max_counts`]) maps to `32767`, so the
+    /// range maps onto the Q15 interval `[-1.0, 1.0)`, and zero maps to zero.
+    /// Readings beyond the rated full scale saturate at `32767` (or `-32768`).
+    /// Only integer arithmetic is used, rounding towards zero.
+    #[must_use]
+    pub fn to_q15(&self, rng: FullScale) -> (i16, i16, i16) {
+        let full_scale = i32::from(rng.max_counts());
+        let convert = |val: i16| {
+            let q = i32::from(val) * i32::from(i16::MAX) / full_scale;
+            // Clamped, so always fits
+            #[allow(clippy::cast_possible_truncation)]
+            let q = q.clamp(i32::from(i16::MIN), i32::from(i16::MAX)) as i16;
+            q
+        };
+        (convert(self.x), convert(self.y), convert(self.z))
+    }
+
+    /// Calculate the heading of the field in the X-Y plane, in hundredths of a degree in the
+    /// range `[0, 36000)`.
+    ///
+    /// The reading must be in the sensor's own frame ([`Frame::Raw`]), with the sensor level and
+    /// its X axis pointing forwards: the heading is then clockwise from the X axis, as in
+    /// `QMC8553L::heading`, so facing east the field points along +Y and this gives `9000`.
+    /// Only integer arithmetic is used: the arctangent is approximated with a polynomial which
+    /// is accurate to within about 0.3°.
+    /// A reading with no horizontal component gives `0`.
+    #[must_use]
+    pub fn heading_centidegrees(&self) -> u16 {
+        const SCALE: i64 = 10_000;
+
+        let (x, y) = (i64::from(self.x), i64::from(self.y));
+        let (abs_x, abs_y) = (x.abs(), y.abs());
+        if abs_x == 0 && abs_y == 0 {
+            return 0;
+        }
+
+        // Reduce to an angle in [0, 45°], where atan(z) ≈ 45z + 15.64z(1 - z) degrees
+        let (num, den) = (abs_x.min(abs_y), abs_x.max(abs_y));
+        let z = num * SCALE / den;
+        let mut angle = 4500 * z / SCALE + 1564 * z * (SCALE - z) / (SCALE * SCALE);
+        if abs_y > abs_x {
+            angle = 9000 - angle;
+        }
+        if x < 0 {
+            angle = 18000 - angle;
+        }
+        if y < 0 {
+            angle = 36000 - angle;
+        }
+        // Always in [0, 36000]
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let angle = (angle % 36000) as u16;
+        angle
+    }
+
+    /// Encode the reading as bytes, eg. for sending over a serial link.
+    ///
+    /// The layout is stable: the X, Y and Z axes in that order, each as a little-endian `i16`
+    /// (so bytes `0..2` are X, `2..4` are Y and `4..6` are Z, least significant byte first).
+    /// Decode with [`Self::from_le_bytes`].
+    #[must_use]
+    pub fn to_le_bytes(&self) -> [u8; 6] {
+        let [x0, x1] = self.x.to_le_bytes();
+        let [y0, y1] = self.y.to_le_bytes();
+        let [z0, z1] = self.z.to_le_bytes();
+        [x0, x1, y0, y1, z0, z1]
+    }
+
+    /// Decode a reading encoded with [`Self::to_le_bytes`].
+    #[must_use]
+    pub fn from_le_bytes(bytes: &[u8; 6]) -> Self {
+        Self {
+            x: i16::from_le_bytes([bytes[0], bytes[1]]),
+            y: i16::from_le_bytes([bytes[2], bytes[3]]),
+            z: i16::from_le_bytes([bytes[4], bytes[5]]),
+        }
+    }
+
+    /// Reduce the reading to a single byte per axis, in the order X, Y, Z, eg. for a heartbeat
+    /// over a very constrained link.
+    ///
+    /// Each byte is the top 8 bits of the axis in offset-binary: `0` is `i16::MIN`, `128` is
+    /// zero and `255` is close to `i16::MAX`.
+    /// This is very lossy: each step is 256 counts, so small fields all read as `127` or `128`.
+    /// Only use it for quick-glance diagnostics.
+    #[must_use]
+    pub fn coarse(&self) -> [u8; 3] {
+        // Flipping the sign bit converts two's complement to offset-binary
+        <[i16; 3]>::from(*self).map(|val| val.to_be_bytes()[0] ^ 0x80)
+    }
+
+    /// Format the reading as a line of CSV, `x,y,z\n`, into `buf`, returning the number of bytes
+    /// written.
+    ///
+    /// This is for data loggers (eg. writing to an SD card), and avoids the `core::fmt`
+    /// machinery.
+    /// A buffer of [`Self::CSV_MAX_LEN`] bytes always fits the record.
+    /// If `buf` is too short for this record, nothing is written and `0` is returned, so a
+    /// partial record is never written.
+    pub fn to_csv_bytes(&self, buf: &mut [u8]) -> usize {
+        let mut record = [0; Self::CSV_MAX_LEN];
+        let mut len = 0;
+        for (i, val) in <[i16; 3]>::from(*self).into_iter().enumerate() {
+            len += write_decimal(val, &mut record[len..]);
+            record[len] = if i == 2 { b'\n' } else { b',' };
+            len += 1;
+        }
+        let Some(out) = buf.get_mut(..len) else {
+            return 0;
+        };
+        out.copy_from_slice(&record[..len]);
+        len
+    }
+
+    /// Get the direction of the field as a unit vector, in the order X, Y, Z.
+    ///
+    /// Returns [`None`] if the field has zero magnitude, since it then has no direction.
+    #[cfg(feature = "float")]
+    #[must_use]
+    pub fn unit_vector(&self) -> Option<(f32, f32, f32)> {
+        let (x, y, z) = (f32::from(self.x), f32::from(self.y), f32::from(self.z));
+        let magnitude = (x * x + y * y + z * z).sqrt();
+        if magnitude == 0.0 {
+            return None;
+        }
+        Some((x / magnitude, y / magnitude, z / magnitude))
+    }
+
+    /// Calculate the angle between this reading and `other`, in degrees in the range `[0, 180]`.
+    ///
+    /// Returns `None` if either reading has zero magnitude, since it then has no direction.
+    #[cfg(feature = "float")]
+    #[must_use]
+    pub fn angle_to(&self, other: Measurement) -> Option<f32> {
+        let (ax, ay, az) = self.unit_vector()?;
+        let (bx, by, bz) = other.unit_vector()?;
+        // Rounding can push the dot product of unit vectors slightly out of range
+        let cos = (ax * bx + ay * by + az * bz).clamp(-1.0, 1.0);
+        Some(cos.acos().to_degrees())
+    }
+}
+
+/// Write `val` in decimal to the start of `buf`, returning the number of bytes written.
+///
+/// `buf` must have room for 6 bytes.
+fn write_decimal(val: i16, buf: &mut [u8]) -> usize {
+    let mut len = 0;
+    if val < 0 {
+        buf[0] = b'-';
+        len += 1;
+    }
+    let mut digits = [0; 5];
+    let mut count = 0;
+    let mut rest = val.unsigned_abs();
+    loop {
+        // A single decimal digit always fits
+        #[allow(clippy::cast_possible_truncation)]
+        let digit = (rest % 10) as u8;
+        digits[count] = b'0' + digit;
+        count += 1;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+    for digit in digits[..count].iter().rev() {
+        buf[len] = *digit;
+        len += 1;
+    }
+    len
+}
+
+impl From<(i16, i16, i16)> for Measurement {
+    fn from((x, y, z): (i16, i16, i16)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl From<Measurement> for (i16, i16, i16) {
+    fn from(m: Measurement) -> Self {
+        (m.x, m.y, m.z)
+    }
+}
+
+impl From<[i16; 3]> for Measurement {
+    fn from([x, y, z]: [i16; 3]) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl From<Measurement> for [i16; 3] {
+    fn from(m: Measurement) -> Self {
+        [m.x, m.y, m.z]
+    }
+}
+
+/// Formats as `(x, y, z)`, in raw counts.
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for Measurement {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        ufmt::uwrite!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+/// Iterate over the axes of a [`Measurement`], in the order X, Y, Z.
+///
+/// ```
+/// # use qmc5883l::Measurement;
+/// let m = Measurement { x: 1, y: 2, z: 3 };
+/// for (axis, val) in m {
+///     println!("{:?}: {}", axis, val);
+/// }
+/// ```
+impl IntoIterator for Measurement {
+    type Item = (Axis, i16);
+    type IntoIter = core::array::IntoIter<(Axis, i16), 3>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        [(Axis::X, self.x), (Axis::Y, self.y), (Axis::Z, self.z)].into_iter()
+    }
+}
+
+/// A remapping of the sensor's axes, for boards where the sensor isn't mounted aligned with the
+/// rest of the system.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+pub struct AxisRemap {
+    /// The sensor axis used for each output axis, in the order X, Y, Z.
+    pub source: [Axis; 3],
+    /// Whether to negate each output axis, in the order X, Y, Z.
+    ///
+    /// Negating `i16::MIN` saturates to `i16::MAX`.
+    pub negate: [bool; 3],
+}
+
+impl AxisRemap {
+    /// The identity remapping, which leaves readings untouched.
+    pub const IDENTITY: Self = Self {
+        source: [Axis::X, Axis::Y, Axis::Z],
+        negate: [false; 3],
+    };
+
+    /// Apply the remapping to a reading.
+    #[must_use]
+    pub fn apply(&self, m: Measurement) -> Measurement {
+        let mut out = [0; 3];
+        for (i, val) in out.iter_mut().enumerate() {
+            let raw = m.get(self.source[i]);
+            *val = if self.negate[i] {
+                raw.saturating_neg()
+            } else {
+                raw
+            };
+        }
+        Measurement::from(out)
+    }
+}
+
+impl Default for AxisRemap {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// A coordinate frame for the output of the sensor.
+///
+/// The sensor's own frame is right-handed, with the X and Y axes in the plane of the package and
+/// Z pointing up out of its top, as marked on the datasheet.
+/// The other frames assume the sensor's X axis points forwards, so its Y axis points left.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Frame {
+    /// The sensor's own frame: readings are left untouched.
+    #[default]
+    Raw,
+    /// North-East-Down: `(x, -y, -z)`.
+    Ned,
+    /// East-North-Up: `(-y, x, z)`.
+    Enu,
+}
+
+impl Frame {
+    /// Get the remapping which converts readings from the sensor's frame to this one.
+    #[must_use]
+    pub fn remap(self) -> AxisRemap {
+        match self {
+            Self::Raw => AxisRemap::IDENTITY,
+            Self::Ned => AxisRemap {
+                source: [Axis::X, Axis::Y, Axis::Z],
+                negate: [false, true, true],
+            },
+            Self::Enu => AxisRemap {
+                source: [Axis::Y, Axis::X, Axis::Z],
+                negate: [true, false, false],
+            },
+        }
+    }
+
+    /// Convert a reading in this frame back to the sensor's own frame: the inverse of
+    /// [`Self::remap`].
+    #[must_use]
+    pub fn to_sensor(self, m: Measurement) -> Measurement {
+        match self {
+            Self::Raw => m,
+            // Negating Y and Z is its own inverse
+            Self::Ned => Self::Ned.remap().apply(m),
+            Self::Enu => Measurement::from([m.y, m.x.saturating_neg(), m.z]),
+        }
+    }
+}
+
+// All of the arithmetic below saturates at the bounds of `i16` rather than wrapping, so eg. the
+// difference between two readings near opposite extremes will be pinned to `i16::MAX`/`i16::MIN`.
+
+/// Axis-wise addition, saturating at [`i16::MAX`] and [`i16::MIN`].
+impl Add for Measurement {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x.saturating_add(rhs.x),
+            y: self.y.saturating_add(rhs.y),
+            z: self.z.saturating_add(rhs.z),
+        }
+    }
+}
+
+/// Axis-wise subtraction, saturating at [`i16::MAX`] and [`i16::MIN`].
+///
+/// Useful for finding the change in field between two consecutive readings.
+impl Sub for Measurement {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x.saturating_sub(rhs.x),
+            y: self.y.saturating_sub(rhs.y),
+            z: self.z.saturating_sub(rhs.z),
+        }
+    }
+}
+
+/// Scale every axis by a constant, saturating at [`i16::MAX`] and [`i16::MIN`].
+impl Mul<i16> for Measurement {
+    type Output = Self;
+
+    fn mul(self, rhs: i16) -> Self::Output {
+        Self {
+            x: self.x.saturating_mul(rhs),
+            y: self.y.saturating_mul(rhs),
+            z: self.z.saturating_mul(rhs),
+        }
+    }
+}
+
+/// Divide every axis by a constant.
+///
+/// The only case which can saturate is `i16::MIN / -1`, which gives [`i16::MAX`].
+///
+/// # Panics
+///
+/// Panics if `rhs` is zero.
+impl Div<i16> for Measurement {
+    type Output = Self;
+
+    fn div(self, rhs: i16) -> Self::Output {
+        Self {
+            x: self.x.saturating_div(rhs),
+            y: self.y.saturating_div(rhs),
+            z: self.z.saturating_div(rhs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iteration_order() {
+        let m = Measurement { x: 1, y: -2, z: 3 };
+        let mut iter = m.into_iter();
+
+        assert_eq!(iter.next(), Some((Axis::X, 1)));
+        assert_eq!(iter.next(), Some((Axis::Y, -2)));
+        assert_eq!(iter.next(), Some((Axis::Z, 3)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn frames_invert() {
+        let m = Measurement::from([1, -2, 3]);
+        for frame in [Frame::Raw, Frame::Ned, Frame::Enu] {
+            assert_eq!(frame.to_sensor(frame.remap().apply(m)), m);
+        }
+    }
+
+    #[test]
+    fn integer_heading() {
+        let heading = |x, y| Measurement::from([x, y, 0]).heading_centidegrees();
+        assert_eq!(heading(1000, 0), 0);
+        // Facing east, north is to the left: along +Y in the sensor's frame
+        assert_eq!(heading(0, 1000), 9000);
+        assert_eq!(heading(-1000, 0), 18000);
+        assert_eq!(heading(0, -1000), 27000);
+        assert_eq!(heading(0, 0), 0);
+        for (x, y, expected) in [(1000, 1000, 4500), (1000, 577, 3000), (-500, -866, 24000)] {
+            assert!(heading(x, y).abs_diff(expected) <= 30);
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let m = Measurement::from([1, -2, i16::MIN]);
+        let bytes = m.to_le_bytes();
+        assert_eq!(bytes, [0x01, 0x00, 0xfe, 0xff, 0x00, 0x80]);
+        assert_eq!(Measurement::from_le_bytes(&bytes), m);
+    }
+
+    #[test]
+    fn coarse_bytes() {
+        let m = Measurement::from([i16::MIN, 0, i16::MAX]);
+        assert_eq!(m.coarse(), [0, 128, 255]);
+        assert_eq!(Measurement::from([-1, 255, 256]).coarse(), [127, 128, 129]);
+    }
+
+    #[test]
+    fn array_round_trip() {
+        let arr = [i16::MIN, 0, i16::MAX];
+        let m = Measurement::from(arr);
+
+        assert_eq!((m.x, m.y, m.z), (i16::MIN, 0, i16::MAX));
+        assert_eq!(<[i16; 3]>::from(m), arr);
+    }
+
+    #[test]
+    fn arithmetic_saturates() {
+        let max = Measurement::from([i16::MAX; 3]);
+        let min = Measurement::from([i16::MIN; 3]);
+        let one = Measurement::from([1; 3]);
+
+        assert_eq!(max + one, max);
+        assert_eq!(min - one, min);
+        assert_eq!(max - min, max);
+        assert_eq!(min * 2, min);
+        assert_eq!(min / -1, max);
+    }
+
+    #[test]
+    fn arithmetic_in_range() {
+        let a = Measurement::from([10, -20, 30]);
+        let b = Measurement::from([1, 2, -3]);
+
+        assert_eq!(a + b, Measurement::from([11, -18, 27]));
+        assert_eq!(a - b, Measurement::from([9, -22, 33]));
+        assert_eq!(a * 2, Measurement::from([20, -40, 60]));
+        assert_eq!(a / 10, Measurement::from([1, -2, 3]));
+    }
+
+    #[test]
+    fn remap() {
+        let m = Measurement::from([1, i16::MIN, 3]);
+        let remap = AxisRemap {
+            source: [Axis::Y, Axis::Z, Axis::X],
+            negate: [true, false, true],
+        };
+
+        assert_eq!(AxisRemap::IDENTITY.apply(m), m);
+        assert_eq!(remap.apply(m), Measurement::from([i16::MAX, 3, -1]));
+    }
+
+    #[test]
+    fn saturation() {
+        assert!(!Measurement::default().is_saturated());
+        assert!(!Measurement::from([i16::MIN + 1, i16::MAX - 1, 0]).is_saturated());
+        assert!(Measurement::from([i16::MAX, 0, 0]).is_saturated());
+        assert!(Measurement::from([0, i16::MIN, 0]).is_saturated());
+        assert!(Measurement::from([0, 0, i16::MAX]).is_saturated());
+    }
+
+    #[test]
+    fn frames() {
+        let m = Measurement::from([1, 2, 3]);
+
+        assert_eq!(Frame::Raw.remap().apply(m), m);
+        assert_eq!(Frame::Ned.remap().apply(m), Measurement::from([1, -2, -3]));
+        assert_eq!(Frame::Enu.remap().apply(m), Measurement::from([-2, 1, 3]));
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn unit_vector() {
+        let (x, y, z) = Measurement::from([300, 0, -400]).unit_vector().unwrap();
+        assert!((x - 0.6).abs() < 0.001);
+        assert!(y.abs() < 0.001);
+        assert!((z + 0.8).abs() < 0.001);
+
+        assert_eq!(Measurement::default().unit_vector(), None);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn angle_between_readings() {
+        let x = Measurement::from([500, 0, 0]);
+        assert!((x.angle_to(Measurement::from([0, 300, 0])).unwrap() - 90.0).abs() < 0.1);
+        assert!((x.angle_to(Measurement::from([-20, 0, 0])).unwrap() - 180.0).abs() < 0.1);
+        assert!(x.angle_to(x).unwrap().abs() < 0.1);
+        assert!((x.angle_to(Measurement::from([100, 100, 0])).unwrap() - 45.0).abs() < 0.1);
+        assert_eq!(x.angle_to(Measurement::default()), None);
+    }
+
+    #[test]
+    fn q15() {
+        let full = FullScale::RNG2G.max_counts();
+        assert_eq!(Measurement::default().to_q15(FullScale::RNG2G), (0, 0, 0));
+        assert_eq!(
+            Measurement::from([full, -full, full / 2]).to_q15(FullScale::RNG2G),
+            (i16::MAX, -i16::MAX, 16383)
+        );
+        assert_eq!(
+            Measurement::from([i16::MAX, i16::MIN, 1]).to_q15(FullScale::RNG8G),
+            (i16::MAX, i16::MIN, 1)
+        );
+    }
+
+    #[test]
+    fn milligauss() {
+        let m = Measurement::from([12_000, -6_000, 3_000]);
+        assert_eq!(m.to_milligauss(FullScale::RNG2G), (1000, -500, 250));
+        assert_eq!(m.to_milligauss(FullScale::RNG8G), (4000, -2000, 1000));
+
+        let extreme = Measurement::from([i16::MAX, i16::MIN, 0]);
+        assert_eq!(
+            extreme.to_milligauss(FullScale::RNG8G),
+            (10_922, -10_922, 0)
+        );
+    }
+
+    #[test]
+    fn milligauss_rounded() {
+        // 12 counts per milliGauss in the 2G range: 6 counts is exactly half
+        let halves = Measurement::from([6, -6, 18]);
+        assert_eq!(halves.to_milligauss(FullScale::RNG2G), (0, 0, 1));
+        assert_eq!(halves.to_milligauss_rounded(FullScale::RNG2G), (1, -1, 2));
+
+        let m = Measurement::from([5, -7, 0]);
+        assert_eq!(m.to_milligauss(FullScale::RNG2G), (0, 0, 0));
+        assert_eq!(m.to_milligauss_rounded(FullScale::RNG2G), (0, -1, 0));
+
+        // 3 counts per milliGauss in the 8G range
+        let extreme = Measurement::from([i16::MAX, i16::MIN, 2]);
+        assert_eq!(
+            extreme.to_milligauss_rounded(FullScale::RNG8G),
+            (10_922, -10_923, 1)
+        );
+    }
+
+    #[test]
+    fn csv_record() {
+        let mut buf = [0; Measurement::CSV_MAX_LEN];
+        let len = Measurement::from([12, -3, 0]).to_csv_bytes(&mut buf);
+        assert_eq!(&buf[..len], b"12,-3,0\n");
+
+        let worst = Measurement::from([i16::MIN; 3]);
+        assert_eq!(worst.to_csv_bytes(&mut buf), Measurement::CSV_MAX_LEN);
+        assert_eq!(&buf[..], b"-32768,-32768,-32768\n");
+
+        let len = Measurement::from([i16::MAX, 100, -1]).to_csv_bytes(&mut buf);
+        assert_eq!(&buf[..len], b"32767,100,-1\n");
+
+        // Too short for the record: nothing is written
+        let mut short = [0; 4];
+        assert_eq!(Measurement::from([12, -3, 0]).to_csv_bytes(&mut short), 0);
+        assert_eq!(short, [0; 4]);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Measurement::from([1, -20, 300]).to_string(),
+            "(1, -20, 300)"
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+/// An I2C bus proxy which selects a channel on a multiplexer before every transaction.
+///
+/// Every QMC5883L has the same address, so several can only share a bus behind a multiplexer
+/// such as the TCA9548A.
+/// Give each driver its own proxy, with a `select` closure which switches the multiplexer to
+/// that sensor's channel: the channel is then selected before each of the driver's
+/// transactions, however the drivers' calls are interleaved.
+/// The bus must be shareable between the proxies, eg. with the `shared-bus` crate.
+///
+/// The driver never relies on state held by the bus between transactions (multi-step
+/// operations such as read-modify-write re-address the device every time), so it works
+/// unchanged behind a proxy.
+/// Selecting the channel costs a write on every transaction: the closure can skip it if it
+/// knows the right channel is already selected.
+///
+/// ```no_run
+/// # fn main() {
+/// # let bus = embedded_hal_mock::i2c::Mock::new(&[]);
+/// use embedded_hal::i2c::I2c;
+/// use qmc5883l::{settings::Settings, MuxedI2c, QMC8553L};
+///
+/// // Select channel 2 on a TCA9548A at its default address
+/// let proxy = MuxedI2c::new(bus, |bus: &mut embedded_hal_mock::i2c::Mock| {
+///     bus.write(0x70, &[1 << 2])
+/// });
+/// let mag = QMC8553L::new(proxy, Settings::default()).unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MuxedI2c<I, F> {
+    i2c: I,
+    select: F,
+}
+
+impl<I, F> MuxedI2c<I, F>
+where
+    I: I2c,
+    F: FnMut(&mut I) -> Result<(), I::Error>,
+{
+    /// Wrap a bus, calling `select` to switch the multiplexer before every transaction.
+    pub fn new(i2c: I, select: F) -> Self {
+        Self { i2c, select }
+    }
+
+    /// Destroy the proxy, returning the bus.
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
+
+impl<I: ErrorType, F> ErrorType for MuxedI2c<I, F> {
+    type Error = I::Error;
+}
+
+impl<I, F> I2c for MuxedI2c<I, F>
+where
+    I: I2c,
+    F: FnMut(&mut I) -> Result<(), I::Error>,
+{
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        (self.select)(&mut self.i2c)?;
+        self.i2c.read(address, read)
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        (self.select)(&mut self.i2c)?;
+        self.i2c.write(address, write)
+    }
+
+    fn write_iter<B>(&mut self, address: u8, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        (self.select)(&mut self.i2c)?;
+        self.i2c.write_iter(address, bytes)
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        (self.select)(&mut self.i2c)?;
+        self.i2c.write_read(address, write, read)
+    }
+
+    fn write_iter_read<B>(
+        &mut self,
+        address: u8,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        (self.select)(&mut self.i2c)?;
+        self.i2c.write_iter_read(address, bytes, buffer)
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        (self.select)(&mut self.i2c)?;
+        self.i2c.transaction(address, operations)
+    }
+
+    fn transaction_iter<'a, O>(&mut self, address: u8, operations: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = Operation<'a>>,
+    {
+        (self.select)(&mut self.i2c)?;
+        self.i2c.transaction_iter(address, operations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{settings::Settings, QMC8553L};
+    use embedded_hal_mock::i2c::{Mock, Transaction};
+
+    const MUX: u8 = 0x70;
+    const ADDR: u8 = QMC8553L::<Mock>::DEFAULT_ADDR;
+
+    fn proxy(
+        bus: &Mock,
+        channel: u8,
+    ) -> MuxedI2c<Mock, impl FnMut(&mut Mock) -> Result<(), <Mock as ErrorType>::Error>> {
+        MuxedI2c::new(bus.clone(), move |bus: &mut Mock| {
+            bus.write(MUX, &[1 << channel])
+        })
+    }
+
+    #[test]
+    fn selects_before_every_transaction() {
+        let mut expectations = Vec::new();
+        for (channel, temp) in [(0u8, 100i16), (3, 200), (0, 300)] {
+            expectations.push(Transaction::write(MUX, vec![1 << channel]));
+            expectations.push(Transaction::write_read(
+                ADDR,
+                vec![0x07],
+                temp.to_le_bytes().to_vec(),
+            ));
+        }
+        let mut bus = Mock::new(&expectations);
+
+        // Build the drivers without touching the device, since initialisation would interleave
+        // many transactions
+        let mut first = QMC8553L::unconfigured(proxy(&bus, 0), Settings::default(), ADDR);
+        let mut second = QMC8553L::unconfigured(proxy(&bus, 3), Settings::default(), ADDR);
+        assert_eq!(first.get_temp().unwrap(), 100);
+        assert_eq!(second.get_temp().unwrap(), 200);
+        assert_eq!(first.get_temp().unwrap(), 300);
+
+        bus.done();
+    }
+}
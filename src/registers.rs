@@ -1,10 +1,15 @@
 use bitflags::bitflags;
-#[cfg(feature = "defmt")]
+#[cfg(all(feature = "defmt", feature = "log"))]
 use defmt::trace;
+#[cfg(feature = "trace")]
+use defmt::trace as bus_trace;
 use embedded_hal::i2c::I2c;
 
 const SRP_ADDR: u8 = 0x0b;
 
+/// The raw contents of the X, Y and Z data registers.
+pub(crate) type RawData = (i16, i16, i16);
+
 #[allow(clippy::upper_case_acronyms)]
 pub(crate) enum Register16 {
     X = 0x00,
@@ -16,7 +21,7 @@ pub(crate) enum Register16 {
 macro_rules! flag_getter {
     ($funcname:ident -> $reg:ident) => {
         fn $funcname(&mut self) -> Result<$reg, I::Error> {
-            #[cfg(feature = "defmt")]
+            #[cfg(all(feature = "defmt", feature = "log"))]
             trace!("Reading flags from {}", FlagRegister::$reg);
             Ok(<$reg>::from_bits_truncate(
                 self.read_raw(FlagRegister::$reg as u8)?,
@@ -27,7 +32,7 @@ macro_rules! flag_getter {
 macro_rules! flag_setter {
     ($funcname:ident -> $reg:ident) => {
         fn $funcname(&mut self, val: $reg) -> Result<(), I::Error> {
-            #[cfg(feature = "defmt")]
+            #[cfg(all(feature = "defmt", feature = "log"))]
             trace!("Writing flags to {}", FlagRegister::$reg);
             self.write_raw(FlagRegister::$reg as u8, val.bits())
         }
@@ -39,7 +44,75 @@ pub(crate) enum FlagRegister {
     Control2 = 0x0a,
 }
 
+/// The documented registers of the device, for use with the raw register API (see
+/// [`crate::QMC8553L::read_register`]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+#[repr(u8)]
+pub enum RegisterAddr {
+    /// X axis data, low byte.
+    XLsb = 0x00,
+    /// X axis data, high byte.
+    XMsb = 0x01,
+    /// Y axis data, low byte.
+    YLsb = 0x02,
+    /// Y axis data, high byte.
+    YMsb = 0x03,
+    /// Z axis data, low byte.
+    ZLsb = 0x04,
+    /// Z axis data, high byte.
+    ZMsb = 0x05,
+    /// The [`Status`] register.
+    Status = 0x06,
+    /// Temperature data, low byte.
+    TempLsb = 0x07,
+    /// Temperature data, high byte.
+    TempMsb = 0x08,
+    /// Control register 1, holding the [`crate::settings::Settings`] and mode.
+    Control1 = 0x09,
+    /// The [`Control2`] register.
+    Control2 = 0x0a,
+    /// The SET/RESET period register.
+    SetResetPeriod = 0x0b,
+    /// The chip ID register.
+    ChipId = 0x0d,
+}
+
+impl RegisterAddr {
+    /// Check whether the datasheet documents the register as writable.
+    ///
+    /// Only the configuration registers are: [`Self::Control1`], [`Self::Control2`] and
+    /// [`Self::SetResetPeriod`].
+    /// The data, status, temperature and chip ID registers are read-only.
+    #[must_use]
+    pub fn is_writable(self) -> bool {
+        matches!(self, Self::Control1 | Self::Control2 | Self::SetResetPeriod)
+    }
+}
+
+impl From<RegisterAddr> for u8 {
+    fn from(reg: RegisterAddr) -> Self {
+        reg as u8
+    }
+}
+
+/// Check whether a raw register address is documented as writable.
+pub(crate) fn is_writable(regaddr: u8) -> bool {
+    [
+        RegisterAddr::Control1,
+        RegisterAddr::Control2,
+        RegisterAddr::SetResetPeriod,
+    ]
+    .into_iter()
+    .any(|reg| u8::from(reg) == regaddr)
+}
+
 bitflags! {
+    /// The status register of the device.
+    ///
+    /// Read with [`crate::QMC8553L::status`], then check individual flags with eg.
+    /// `status.contains(Status::OVL)`.
     pub struct Status: u8 {
         /// Data Skip.
         ///
@@ -60,40 +133,214 @@ bitflags! {
         const DRDY = 0b0001;
     }
 
+    /// The second control register of the device.
     pub struct Control2: u8 {
         /// Soft Reset flag.
+        ///
+        /// Writing this restores all registers to their default values.
+        /// It clears itself once the reset is complete.
         const SOFT_RST = 0b1000_0000;
         /// Rolling Pointer Flag.
         ///
-        /// Will automatically roll the pointer over when reading from the data registers.
+        /// Will automatically roll the pointer over when reading from the data registers: after
+        /// the status register (`0x06`), the pointer wraps back to `0x00` rather than moving on
+        /// to the temperature registers.
+        /// The multi-byte reads in this driver rely on this being set.
         const ROL_PNT = 0b0100_0000;
-        /// Enable interrupts.
+        /// Interrupt pin flag.
         ///
-        /// TODO: currently unused.
+        /// Note that this is active-low: when **unset**, the interrupt pin is enabled and will
+        /// signal when new data is ready.
+        /// When set, the interrupt pin is disabled.
         const INT_ENB = 0b0001;
     }
 }
 
-fn i16_from_le(val: &[u8]) -> i16 {
-    bytemuck::cast([val[1], val[0]])
+// bitflags doesn't derive these, so format the raw bits
+#[cfg(feature = "defmt")]
+impl defmt::Format for Status {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Status({=u8:#06b})", self.bits());
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Control2 {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Control2({=u8:#010b})", self.bits());
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for Status {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        ufmt::uwrite!(f, "Status({})", self.bits())
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for Control2 {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        ufmt::uwrite!(f, "Control2({})", self.bits())
+    }
+}
+
+/// A typed view of the [`Control2`] register, for setting all of its flags in one write.
+///
+/// Apply with [`crate::QMC8553L::apply_control2`].
+/// The default matches the state the driver leaves the device in after a reset.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct Control2Config {
+    /// Perform a soft reset, restoring all registers to their default values.
+    ///
+    /// This clears itself once the reset is complete, so always reads back as `false`.
+    pub soft_reset: bool,
+    /// Roll the pointer over when reading from the data registers.
+    ///
+    /// The multi-byte reads in this driver rely on this being set.
+    pub rollover: bool,
+    /// Enable the interrupt pin, which signals when new data is ready.
+    pub interrupt: bool,
+}
+
+impl Default for Control2Config {
+    fn default() -> Self {
+        Self {
+            soft_reset: false,
+            rollover: true,
+            interrupt: true,
+        }
+    }
+}
+
+impl From<Control2Config> for Control2 {
+    fn from(cfg: Control2Config) -> Self {
+        let mut flags = Self::empty();
+        flags.set(Self::SOFT_RST, cfg.soft_reset);
+        flags.set(Self::ROL_PNT, cfg.rollover);
+        // The flag is active-low
+        flags.set(Self::INT_ENB, !cfg.interrupt);
+        flags
+    }
+}
+
+impl From<Control2> for Control2Config {
+    fn from(flags: Control2) -> Self {
+        Self {
+            soft_reset: flags.contains(Control2::SOFT_RST),
+            rollover: flags.contains(Control2::ROL_PNT),
+            interrupt: !flags.contains(Control2::INT_ENB),
+        }
+    }
+}
+
+/// Decode a 16-bit register pair as little-endian (LSB first), as the datasheet specifies.
+///
+/// The bytes are given in register order.
+/// Together with [`decode_i16_be`], this is useful for checking which byte order a part
+/// actually uses (see [`ByteOrder`]).
+#[must_use]
+pub fn decode_i16_le(bytes: &[u8; 2]) -> i16 {
+    i16::from_le_bytes(*bytes)
+}
+
+/// Decode a 16-bit register pair as big-endian (MSB first), as used by some clones.
+///
+/// The bytes are given in register order: see [`decode_i16_le`].
+#[must_use]
+pub fn decode_i16_be(bytes: &[u8; 2]) -> i16 {
+    i16::from_be_bytes(*bytes)
+}
+
+/// The order of the bytes in the device's 16-bit registers.
+///
+/// Set with [`crate::QMC8553L::set_byte_order`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum ByteOrder {
+    /// LSB first, as specified by the datasheet.
+    #[default]
+    LittleEndian,
+    /// MSB first, as used by some clones.
+    BigEndian,
+}
+
+impl ByteOrder {
+    /// Decode a 16-bit register pair, given in register order.
+    pub(crate) fn decode(self, val: &[u8]) -> i16 {
+        let pair = [val[0], val[1]];
+        match self {
+            Self::LittleEndian => decode_i16_le(&pair),
+            Self::BigEndian => decode_i16_be(&pair),
+        }
+    }
 }
 
 pub(crate) trait Registers<I: I2c> {
-    const ADDR: u8;
+    /// The address of the device on the bus.
+    fn addr(&self) -> u8;
+
+    /// The order of the bytes in 16-bit registers.
+    fn byte_order(&self) -> ByteOrder;
 
     fn i2c(&mut self) -> &mut I;
 
+    /// Write `bytes` to the device, starting with the register address.
+    ///
+    /// Every write to the device goes through here, so it can be traced.
+    fn bus_write(&mut self, bytes: &[u8]) -> Result<(), I::Error> {
+        #[cfg(feature = "trace")]
+        bus_trace!("I2C write {=[u8]:#04x}", bytes);
+        let addr = self.addr();
+        self.i2c().write(addr, bytes)
+    }
+
+    /// Read into `buf` from consecutive registers, starting at `regaddr`.
+    ///
+    /// Every read from the device (except [`Self::bus_read_continue`]) goes through here, so it
+    /// can be traced.
+    fn bus_read(&mut self, regaddr: u8, buf: &mut [u8]) -> Result<(), I::Error> {
+        let addr = self.addr();
+        self.i2c().write_read(addr, &[regaddr], buf)?;
+        #[cfg(feature = "trace")]
+        bus_trace!("I2C read {=u8:#04x}: {=[u8]:#04x}", regaddr, buf);
+        Ok(())
+    }
+
+    /// Read into `buf` from consecutive registers, continuing from wherever the device's
+    /// register pointer was left by the previous transaction.
+    ///
+    /// This is only used for diagnostics: like [`Self::bus_read`], it's traced.
+    fn bus_read_continue(&mut self, buf: &mut [u8]) -> Result<(), I::Error> {
+        let addr = self.addr();
+        self.i2c().read(addr, buf)?;
+        #[cfg(feature = "trace")]
+        bus_trace!("I2C read (continued): {=[u8]:#04x}", buf);
+        Ok(())
+    }
+
     fn read_raw(&mut self, regaddr: u8) -> Result<u8, I::Error> {
         let mut val = [0];
-
-        let to_write = [regaddr];
-        self.i2c().write_read(Self::ADDR, &to_write, &mut val)?;
+        self.bus_read(regaddr, &mut val)?;
         Ok(val[0])
     }
 
     fn write_raw(&mut self, regaddr: u8, val: u8) -> Result<(), I::Error> {
-        let to_write = [regaddr, val];
-        self.i2c().write(Self::ADDR, &to_write)
+        self.bus_write(&[regaddr, val])
+    }
+
+    /// Move the device's register pointer without reading or writing a register.
+    fn set_pointer(&mut self, regaddr: u8) -> Result<(), I::Error> {
+        self.bus_write(&[regaddr])
     }
 
     fn read_set_reset_period(&mut self) -> Result<u8, I::Error> {
@@ -103,30 +350,122 @@ pub(crate) trait Registers<I: I2c> {
 
     // Uses pointer rollover to reduce bus load
     fn read_reg16(&mut self, reg: Register16) -> Result<i16, I::Error> {
+        let buf = self.read_reg16_raw(reg)?;
+        Ok(self.byte_order().decode(&buf))
+    }
+
+    /// Read both bytes of a 16-bit register, in register order (LSB first).
+    fn read_reg16_raw(&mut self, reg: Register16) -> Result<[u8; 2], I::Error> {
         let lsb_addr = reg as u8;
         let mut buf = [0; 2];
 
-        self.i2c().write_read(Self::ADDR, &[lsb_addr], &mut buf)?;
-        #[cfg(feature = "defmt")]
+        self.bus_read(lsb_addr, &mut buf)?;
+        #[cfg(all(feature = "defmt", feature = "log"))]
         trace!("Read value {:?} from register at {}", buf, lsb_addr);
-        Ok(i16_from_le(&buf))
+        Ok(buf)
     }
 
-    /// Read all 6 data registers off the device.
+    /// Read consecutive 16-bit registers in a single transaction, starting at `start`.
+    ///
+    /// Uses pointer rollover to reduce bus load.
+    /// Reads at most 3 registers: any further entries in `out` are left untouched.
+    fn read_reg16_span(&mut self, start: Register16, out: &mut [i16]) -> Result<(), I::Error> {
+        let lsb_addr = start as u8;
+        let mut buf = [0; 6];
+        let len = (out.len() * 2).min(buf.len());
+
+        self.bus_read(lsb_addr, &mut buf[..len])?;
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        trace!("Read value {:?} from registers at {}", buf[..len], lsb_addr);
+
+        let order = self.byte_order();
+        for (val, bytes) in out.iter_mut().zip(buf[..len].chunks_exact(2)) {
+            *val = order.decode(bytes);
+        }
+        Ok(())
+    }
+
+    /// Read all 6 data registers off the device, without decoding them.
     ///
     /// Uses pointer rollover to reduce bus load.
-    fn read_data(&mut self) -> Result<(i16, i16, i16), I::Error> {
-        let addr = Register16::X as u8;
+    fn read_data_bytes(&mut self) -> Result<[u8; 6], I::Error> {
+        let regaddr = Register16::X as u8;
         let mut buf = [0; 6];
 
-        self.i2c().write_read(Self::ADDR, &[addr], &mut buf)?;
-        #[cfg(feature = "defmt")]
+        self.bus_read(regaddr, &mut buf)?;
+        #[cfg(all(feature = "defmt", feature = "log"))]
         trace!("Read raw value {:?} from all axis registers", buf);
+        Ok(buf)
+    }
 
+    /// Read all 6 data registers off the device.
+    ///
+    /// Uses pointer rollover to reduce bus load.
+    fn read_data(&mut self) -> Result<RawData, I::Error> {
+        let buf = self.read_data_bytes()?;
+        let order = self.byte_order();
         Ok((
-            i16_from_le(&buf[0..2]),
-            i16_from_le(&buf[2..4]),
-            i16_from_le(&buf[4..6]),
+            order.decode(&buf[0..2]),
+            order.decode(&buf[2..4]),
+            order.decode(&buf[4..6]),
+        ))
+    }
+
+    /// Read all 6 data registers and the status register off the device in one transaction.
+    ///
+    /// Uses pointer rollover to reduce bus load.
+    fn read_data_and_status(&mut self) -> Result<(RawData, Status), I::Error> {
+        let (data, status) = self.read_data_and_raw_status()?;
+        Ok((data, Status::from_bits_truncate(status)))
+    }
+
+    /// Read all 6 data registers and the status register off the device in one transaction,
+    /// without interpreting the status.
+    fn read_data_and_raw_status(&mut self) -> Result<(RawData, u8), I::Error> {
+        let regaddr = Register16::X as u8;
+        let mut buf = [0; 7];
+
+        self.bus_read(regaddr, &mut buf)?;
+        #[cfg(all(feature = "defmt", feature = "log"))]
+        trace!("Read raw value {:?} from axis and status registers", buf);
+
+        let order = self.byte_order();
+        let data = (
+            order.decode(&buf[0..2]),
+            order.decode(&buf[2..4]),
+            order.decode(&buf[4..6]),
+        );
+        Ok((data, buf[6]))
+    }
+
+    /// Read the axis data, then the temperature.
+    ///
+    /// These take a transaction each: with pointer rollover enabled, the pointer wraps back to
+    /// `0x00` after the status register, so reading on from the data never reaches the
+    /// temperature registers.
+    fn read_data_and_temp(&mut self) -> Result<(RawData, i16), I::Error> {
+        let data = self.read_data()?;
+        let temp = self.read_reg16(Register16::TOUT)?;
+        Ok((data, temp))
+    }
+
+    /// Read a 16-bit register one byte at a time.
+    ///
+    /// Doesn't rely on pointer rollover, at the cost of an extra transaction.
+    fn read_reg16_explicit(&mut self, reg: Register16) -> Result<i16, I::Error> {
+        let lsb_addr = reg as u8;
+        let buf = [self.read_raw(lsb_addr)?, self.read_raw(lsb_addr + 1)?];
+        Ok(self.byte_order().decode(&buf))
+    }
+
+    /// Read all 6 data registers off the device one byte at a time.
+    ///
+    /// Doesn't rely on pointer rollover, at the cost of six transactions instead of one.
+    fn read_data_explicit(&mut self) -> Result<RawData, I::Error> {
+        Ok((
+            self.read_reg16_explicit(Register16::X)?,
+            self.read_reg16_explicit(Register16::Y)?,
+            self.read_reg16_explicit(Register16::Z)?,
         ))
     }
 
@@ -140,12 +479,71 @@ pub(crate) trait Registers<I: I2c> {
 }
 
 impl<I: I2c> Registers<I> for crate::QMC8553L<I> {
-    const ADDR: u8 = 0x0D;
+    fn addr(&self) -> u8 {
+        self.addr
+    }
+
+    fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
 
     fn i2c(&mut self) -> &mut I {
-        // We set it off standby since we assume the I2C will be used
-        // TODO: is this ok?
-        self.standby = false;
         &mut self.i2c
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control2_config_round_trip() {
+        assert_eq!(Control2::from(Control2Config::default()), Control2::ROL_PNT);
+        let cfg = Control2Config {
+            soft_reset: true,
+            rollover: false,
+            interrupt: false,
+        };
+        let flags = Control2::from(cfg);
+        assert_eq!(flags, Control2::SOFT_RST | Control2::INT_ENB);
+        assert_eq!(Control2Config::from(flags), cfg);
+    }
+
+    #[test]
+    fn decode_big_endian() {
+        assert_eq!(ByteOrder::BigEndian.decode(&[0x12, 0x34]), 0x1234);
+        assert_eq!(ByteOrder::BigEndian.decode(&[0xff, 0x9c]), -100);
+        assert_eq!(ByteOrder::LittleEndian.decode(&[0x9c, 0xff]), -100);
+    }
+
+    #[test]
+    fn decode_is_little_endian() {
+        assert_eq!(decode_i16_le(&[0x34, 0x12]), 0x1234);
+        assert_eq!(decode_i16_le(&[0xff, 0x7f]), i16::MAX);
+        assert_eq!(decode_i16_le(&[0x00, 0x80]), i16::MIN);
+        assert_eq!(decode_i16_le(&[0x9c, 0xff]), -100);
+        assert_eq!(decode_i16_le(&[0x00, 0x00]), 0);
+        assert_eq!(decode_i16_le(&[0xff, 0xff]), -1);
+    }
+
+    #[test]
+    fn decode_explicit_big_endian() {
+        assert_eq!(decode_i16_be(&[0x12, 0x34]), 0x1234);
+        assert_eq!(decode_i16_be(&[0x7f, 0xff]), i16::MAX);
+        assert_eq!(decode_i16_be(&[0x80, 0x00]), i16::MIN);
+        assert_eq!(decode_i16_be(&[0xff, 0x9c]), -100);
+        assert_eq!(decode_i16_be(&[0x00, 0x00]), 0);
+        assert_eq!(decode_i16_be(&[0xff, 0xff]), -1);
+    }
+
+    #[test]
+    fn decode_orders_are_swapped() {
+        for val in i16::MIN..=i16::MAX {
+            let le = val.to_le_bytes();
+            let be = val.to_be_bytes();
+            assert_eq!(decode_i16_le(&le), val);
+            assert_eq!(decode_i16_be(&be), val);
+            assert_eq!(decode_i16_be(&le), val.swap_bytes());
+        }
+    }
+}
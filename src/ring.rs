@@ -0,0 +1,130 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Error, Measurement, QMC8553L};
+
+/// Collects readings into a fixed-size ring buffer, keeping the most recent `N`.
+///
+/// This decouples the rate at which readings are taken from the rate at which they're used: call
+/// [`Self::poll`] often (eg. from a timer), and read the buffered data at leisure.
+/// Once the buffer is full, each new reading replaces the oldest.
+///
+/// ```no_run
+/// # fn main() {
+/// # let mock_i2c = embedded_hal_mock::i2c::Mock::new(&[]);
+/// use qmc5883l::{QMC8553L, RingSampler, settings::Settings};
+/// let mut mag = QMC8553L::new(mock_i2c, Settings::default()).unwrap();
+///
+/// let mut ring = RingSampler::<16>::new();
+/// ring.poll(&mut mag).unwrap();
+/// for m in ring.iter() {
+///     println!("{}", m);
+/// }
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RingSampler<const N: usize> {
+    buf: [Measurement; N],
+    // Where the next reading will be written
+    next: usize,
+    len: usize,
+}
+
+impl<const N: usize> RingSampler<N> {
+    // Referenced in `new` so that `N == 0` fails to compile
+    const NON_ZERO: () = assert!(N > 0, "RingSampler must hold at least one reading");
+
+    /// Create an empty sampler.
+    #[must_use]
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::NON_ZERO;
+        Self {
+            buf: [Measurement::default(); N],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Check the device for new data, adding it to the buffer if there is any.
+    ///
+    /// Returns whether a reading was added.
+    /// See [`QMC8553L::read_if_ready`].
+    pub fn poll<I: I2c>(&mut self, mag: &mut QMC8553L<I>) -> Result<bool, Error<I::Error>> {
+        match mag.read_if_ready()? {
+            Some(m) => {
+                self.push(m);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Add a reading to the buffer, replacing the oldest if it's full.
+    pub fn push(&mut self, m: Measurement) {
+        self.buf[self.next] = m;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Get the most recent reading, if there is one.
+    #[must_use]
+    pub fn latest(&self) -> Option<Measurement> {
+        if self.len == 0 {
+            return None;
+        }
+        Some(self.buf[(self.next + N - 1) % N])
+    }
+
+    /// Iterate over the buffered readings, from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = Measurement> + '_ {
+        let start = (self.next + N - self.len) % N;
+        (0..self.len).map(move |i| self.buf[(start + i) % N])
+    }
+
+    /// Get the number of buffered readings.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether there are no buffered readings.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discard all buffered readings.
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for RingSampler<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_around() {
+        let mut ring = RingSampler::<3>::new();
+        assert_eq!(ring.latest(), None);
+        assert_eq!(ring.iter().count(), 0);
+
+        for val in 1..=4 {
+            ring.push(Measurement::from([val; 3]));
+        }
+        assert_eq!(ring.len(), 3);
+        assert_eq!(ring.latest(), Some(Measurement::from([4; 3])));
+        let vals: Vec<_> = ring.iter().map(|m| m.x).collect();
+        assert_eq!(vals, [2, 3, 4]);
+
+        ring.clear();
+        assert!(ring.is_empty());
+    }
+}
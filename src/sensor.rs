@@ -0,0 +1,27 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Error, QMC8553L};
+
+/// A generic interface to magnetometers, for sensor-agnostic code such as fusion frameworks.
+///
+/// Implemented by [`QMC8553L`], so the driver can be used wherever a magnetometer is needed.
+pub trait MagnetometerReading {
+    /// The error returned when a reading fails.
+    type Error;
+
+    /// Read the magnetic field, in Gauss, in the order X, Y, Z.
+    ///
+    /// The axes are those of the sensor after any remapping or frame conversion the driver
+    /// applies.
+    fn read_magnetic_field(&mut self) -> Result<[f32; 3], Self::Error>;
+}
+
+impl<I: I2c> MagnetometerReading for QMC8553L<I> {
+    type Error = Error<I::Error>;
+
+    /// Read the field as in [`QMC8553L::read_all_gauss`].
+    fn read_magnetic_field(&mut self) -> Result<[f32; 3], Self::Error> {
+        let (x, y, z) = self.read_all_gauss()?;
+        Ok([x, y, z])
+    }
+}
@@ -0,0 +1,140 @@
+use embedded_hal::i2c::I2c;
+
+use crate::QMC8553L;
+
+/// Raises an alarm when the device's temperature moves too far from a baseline.
+///
+/// This is a coarse thermal monitor built on [`QMC8553L::get_temp`], for using the device
+/// incidentally as a temperature sensor, eg. to notice an enclosure heating up.
+/// The baseline and threshold are in raw counts: the sensor's gain is roughly 100 counts per
+/// °C, but its offset isn't calibrated, so the baseline should be captured from the device
+/// rather than given in °C.
+///
+/// ```no_run
+/// # fn main() {
+/// # let mock_i2c = embedded_hal_mock::i2c::Mock::new(&[]);
+/// use qmc5883l::{QMC8553L, TempMonitor, settings::Settings};
+/// let mut mag = QMC8553L::new(mock_i2c, Settings::default()).unwrap();
+///
+/// // Alarm on a change of roughly 5°C either way
+/// let mut monitor = TempMonitor::new(500);
+/// monitor.capture_baseline(&mut mag, 8).unwrap();
+/// if monitor.check(&mut mag).unwrap() {
+///     println!("Temperature changed");
+/// }
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TempMonitor {
+    baseline: Option<i16>,
+    threshold: u16,
+}
+
+impl TempMonitor {
+    /// Create a monitor which alarms when the temperature deviates from the baseline by more
+    /// than `threshold` raw counts.
+    ///
+    /// There's no baseline yet: see [`Self::check`].
+    #[must_use]
+    pub fn new(threshold: u16) -> Self {
+        Self {
+            baseline: None,
+            threshold,
+        }
+    }
+
+    /// Set the baseline from the mean of `n` temperature readings.
+    ///
+    /// See [`QMC8553L::average_temperature`].
+    pub fn capture_baseline<I: I2c>(
+        &mut self,
+        mag: &mut QMC8553L<I>,
+        n: u8,
+    ) -> Result<i16, I::Error> {
+        let baseline = mag.average_temperature(n)?;
+        self.baseline = Some(baseline);
+        Ok(baseline)
+    }
+
+    /// Set the baseline to a known value, in raw counts.
+    pub fn set_baseline(&mut self, baseline: i16) {
+        self.baseline = Some(baseline);
+    }
+
+    /// Read the temperature, returning whether it's more than the threshold away from the
+    /// baseline.
+    ///
+    /// If no baseline has been set, the reading becomes the baseline, and `false` is returned.
+    /// The baseline doesn't move otherwise, so the alarm persists until the temperature comes
+    /// back within the threshold.
+    pub fn check<I: I2c>(&mut self, mag: &mut QMC8553L<I>) -> Result<bool, I::Error> {
+        let temp = mag.get_temp()?;
+        Ok(self.exceeds(temp))
+    }
+
+    /// Check a temperature reading, in raw counts, against the baseline as in [`Self::check`].
+    pub fn exceeds(&mut self, temp: i16) -> bool {
+        let baseline = *self.baseline.get_or_insert(temp);
+        temp.abs_diff(baseline) > self.threshold
+    }
+
+    /// Get the current baseline, in raw counts.
+    ///
+    /// Returns `None` if none has been set.
+    #[must_use]
+    pub fn baseline(&self) -> Option<i16> {
+        self.baseline
+    }
+
+    /// Forget the baseline, so the next reading starts a new one.
+    pub fn reset(&mut self) {
+        self.baseline = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+    use embedded_hal_mock::i2c::{Mock, Transaction};
+
+    const ADDR: u8 = QMC8553L::<Mock>::DEFAULT_ADDR;
+
+    fn temp_read(temp: i16) -> Transaction {
+        Transaction::write_read(ADDR, vec![0x07], temp.to_le_bytes().to_vec())
+    }
+
+    #[test]
+    fn alarms_beyond_threshold() {
+        let mut i2c = Mock::new(&[
+            temp_read(1000),
+            temp_read(1002),
+            temp_read(1003),
+            temp_read(1302),
+            temp_read(699),
+            temp_read(1000),
+        ]);
+
+        let mut mag = QMC8553L::unconfigured(i2c.clone(), Settings::default(), ADDR);
+        let mut monitor = TempMonitor::new(300);
+        assert_eq!(monitor.capture_baseline(&mut mag, 2), Ok(1001));
+        assert_eq!(monitor.check(&mut mag), Ok(false));
+        // Either side of the baseline
+        assert_eq!(monitor.check(&mut mag), Ok(true));
+        assert_eq!(monitor.check(&mut mag), Ok(true));
+        assert_eq!(monitor.check(&mut mag), Ok(false));
+        assert_eq!(monitor.baseline(), Some(1001));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn first_reading_is_baseline() {
+        let mut monitor = TempMonitor::new(10);
+        assert_eq!(monitor.baseline(), None);
+        assert!(!monitor.exceeds(i16::MIN));
+        assert!(monitor.exceeds(i16::MAX));
+        monitor.reset();
+        assert_eq!(monitor.baseline(), None);
+    }
+}